@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use crate::{Bag, ColorPolicy, Game, Reveal};
+
+/// Returns the smallest bag making every game possible
+pub fn smallest_bag<'a>(games: &[Game<'a>]) -> Bag<'a> {
+    let mut limits: HashMap<&'a str, u32> = HashMap::new();
+
+    for game in games.iter() {
+        for (color, amount) in game.max_used_by_color() {
+            limits
+                .entry(color)
+                .and_modify(|limit| *limit = (*limit).max(amount))
+                .or_insert(amount);
+        }
+    }
+
+    Bag {
+        limits,
+        unseen_colors: ColorPolicy::Allow,
+        unknown_colors: ColorPolicy::Reject,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DrawModel {
+    // the cubes of a reveal go back in the bag before the next reveal
+    PutBack,
+    // the cubes of a reveal stay out of the bag until the end of the game
+    KeepOut,
+}
+
+fn binomial(n: u32, k: u32) -> f64 {
+    if k > n {
+        return 0f64;
+    }
+
+    let k = k.min(n - k);
+
+    (0..k).fold(1f64, |accumulator, i| {
+        accumulator * (n - i) as f64 / (i + 1) as f64
+    })
+}
+
+// a reveal draws as many cubes as it shows, at once and without replacement,
+// so the odds of its exact color composition follow a multivariate hypergeometric law
+fn reveal_probability(reveal: &Reveal, cubes_in_bag: &HashMap<&str, u32>) -> f64 {
+    let total_in_bag: u32 = cubes_in_bag.values().sum();
+
+    if reveal.total() > total_in_bag {
+        return 0f64;
+    }
+
+    let favorable_draws =
+        reveal
+            .amounts_by_color()
+            .iter()
+            .fold(1f64, |accumulator, (color, shown)| {
+                let in_bag = cubes_in_bag.get(color).copied().unwrap_or(0);
+                accumulator * binomial(in_bag, *shown)
+            });
+
+    favorable_draws / binomial(total_in_bag, reveal.total())
+}
+
+/// Returns the probability that a game happens exactly as recorded with the given bag
+pub fn game_probability(game: &Game, bag: &Bag, model: DrawModel) -> f64 {
+    let mut cubes_in_bag: HashMap<&str, u32> = bag
+        .limits
+        .iter()
+        .map(|(color, amount)| (*color, *amount))
+        .collect();
+
+    let mut probability = 1f64;
+
+    for reveal in game.reveals.iter() {
+        probability *= reveal_probability(reveal, &cubes_in_bag);
+
+        if probability == 0f64 {
+            return 0f64;
+        }
+
+        if model == DrawModel::KeepOut {
+            for (color, shown) in reveal.amounts_by_color() {
+                cubes_in_bag
+                    .entry(color)
+                    .and_modify(|amount| *amount = amount.saturating_sub(shown));
+            }
+        }
+    }
+
+    probability
+}
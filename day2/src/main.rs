@@ -1,45 +1,111 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use inference::DrawModel;
+use query::Query;
+
+mod inference;
+mod query;
+
+#[derive(Debug)]
+struct Reveal<'a> {
+    cubes: Vec<(&'a str, u32)>,
+}
+
+impl<'a> From<&'a str> for Reveal<'a> {
+    fn from(reveal: &'a str) -> Self {
+        let cubes = reveal
+            .split(',')
+            .map(|cube_desc| cube_desc.trim())
+            .map(|cube_description| {
+                let cube_description: Vec<&str> = cube_description.split(' ').collect();
+
+                let cube_amount = *cube_description
+                    .first()
+                    .expect("cube should have an amount");
+                let cube_amount: u32 = cube_amount.parse().expect("cube amount should be a number");
+                let cube_color = *cube_description.get(1).expect("cube should have a color");
+
+                (cube_color, cube_amount)
+            })
+            .collect();
+
+        Reveal { cubes }
+    }
+}
+
+impl<'a> Reveal<'a> {
+    fn amounts_by_color(&self) -> HashMap<&'a str, u32> {
+        let mut amounts_by_color = HashMap::new();
+
+        for (color, amount) in self.cubes.iter() {
+            *amounts_by_color.entry(*color).or_insert(0) += amount;
+        }
+
+        amounts_by_color
+    }
+
+    fn amount_of(&self, color: &str) -> u32 {
+        self.cubes
+            .iter()
+            .filter(|(cube_color, _)| *cube_color == color)
+            .map(|(_, amount)| amount)
+            .sum()
+    }
+
+    fn total(&self) -> u32 {
+        self.cubes.iter().map(|(_, amount)| amount).sum()
+    }
+}
+
+impl fmt::Display for Reveal<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cubes: Vec<String> = self
+            .cubes
+            .iter()
+            .map(|(color, amount)| format!("{} {}", amount, color))
+            .collect();
+
+        write!(f, "{}", cubes.join(", "))
+    }
+}
 
 #[derive(Debug)]
 struct Game<'a> {
     id: i32,
-    max_used_by_color: HashMap<&'a str, u32>,
+    reveals: Vec<Reveal<'a>>,
 }
 
 impl<'a> From<&'a str> for Game<'a> {
     fn from(line: &'a str) -> Self {
-        let game_info: Vec<&'a str> = line.split(":").collect();
+        let game_info: Vec<&'a str> = line.split(':').collect();
         let id: i32 = game_info
-            .get(0)
-            .map(|introduction| introduction.split(" ").last())
-            .flatten()
+            .first()
+            .and_then(|introduction| introduction.split(' ').next_back())
             .expect("game description should have game ID before :")
             .parse()
             .expect("game ID should be a number");
 
-        let mut max_used_by_color = HashMap::new();
-
         let game_results = *game_info.get(1).expect("Game should have results");
 
-        let reveals: Vec<&str> = game_results.split(";").collect();
-
-        for reveal in reveals {
-            let cube_descriptions: Vec<&str> = reveal
-                .split(",")
-                .map(|cube_desc| cube_desc.trim())
-                .collect();
+        let reveals = game_results.split(';').map(Reveal::from).collect();
 
-            for cube_description in cube_descriptions {
-                let cube_description: Vec<&str> = cube_description.split(" ").collect();
+        Game { id, reveals }
+    }
+}
 
-                let cube_amount = *cube_description.get(0).expect("cube should have an amount");
-                let cube_amount: u32 = cube_amount.parse().expect("cube amount should be a number");
-                let cube_color = *cube_description.get(1).expect("cube should have a color");
+impl<'a> Game<'a> {
+    fn max_used_by_color(&self) -> HashMap<&'a str, u32> {
+        let mut max_used_by_color = HashMap::new();
 
+        for reveal in self.reveals.iter() {
+            for (color, cube_amount) in reveal.amounts_by_color() {
                 max_used_by_color
-                    .entry(cube_color)
+                    .entry(color)
                     .and_modify(|current_max| {
-                        if (*current_max < cube_amount) {
+                        if *current_max < cube_amount {
                             *current_max = cube_amount;
                         }
                     })
@@ -47,62 +113,322 @@ impl<'a> From<&'a str> for Game<'a> {
             }
         }
 
-        Game {
-            id,
-            max_used_by_color,
+        max_used_by_color
+    }
+
+    fn validates(&self, bag: &Bag<'a>) -> Verdict<'a> {
+        bag.judge(&self.max_used_by_color())
+    }
+
+    /// Returns the first reveal showing more cubes of a color than the bag holds
+    fn breaking_reveal(&self, bag: &Bag) -> Option<(usize, &Reveal<'a>)> {
+        self.reveals.iter().enumerate().find(|(_, reveal)| {
+            reveal
+                .cubes
+                .iter()
+                .any(|(color, _)| match bag.allowance(color) {
+                    Some(allowed) => reveal.amount_of(color) > allowed,
+                    None => false,
+                })
+        })
+    }
+
+    fn reveal_totals(&self) -> Vec<u32> {
+        self.reveals.iter().map(|reveal| reveal.total()).collect()
+    }
+
+    fn fewest_cubes_in_a_reveal(&self) -> Option<u32> {
+        self.reveal_totals().into_iter().min()
+    }
+
+    fn minimum_set_power(&self) -> u32 {
+        self.max_used_by_color().values().product()
+    }
+}
+
+impl fmt::Display for Game<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reveals: Vec<String> = self
+            .reveals
+            .iter()
+            .map(|reveal| reveal.to_string())
+            .collect();
+
+        write!(f, "Game {}: {}", self.id, reveals.join("; "))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColorPolicy {
+    Allow,
+    Reject,
+}
+
+#[derive(Debug)]
+struct Bag<'a> {
+    limits: HashMap<&'a str, u32>,
+    // what to do when a game never shows a color of the bag
+    unseen_colors: ColorPolicy,
+    // what to do when a game shows a color that is not in the bag
+    unknown_colors: ColorPolicy,
+}
+
+#[derive(Debug, PartialEq)]
+struct Violation<'a> {
+    color: &'a str,
+    // None when the game never showed the color
+    observed: Option<u32>,
+    // None when the color is not in the bag
+    allowed: Option<u32>,
+}
+
+impl fmt::Display for Violation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.observed, self.allowed) {
+            (Some(observed), Some(allowed)) => write!(
+                f,
+                "{}: observed {} but only {} allowed",
+                self.color, observed, allowed
+            ),
+            (Some(observed), None) => write!(
+                f,
+                "{}: observed {} but color is not in the bag",
+                self.color, observed
+            ),
+            (None, Some(allowed)) => write!(
+                f,
+                "{}: never observed but {} in the bag",
+                self.color, allowed
+            ),
+            (None, None) => write!(f, "{}: unknown", self.color),
         }
     }
 }
 
-impl Game<'_> {
-    fn validates(&self, max_cubes: &HashMap<&str, u32>) -> bool {
-        for (color, max_amount) in max_cubes.iter() {
-            match self.max_used_by_color.get(*color) {
-                Some(amount) => {
-                    if amount > max_amount {
-                        return false;
-                    }
+#[derive(Debug, PartialEq)]
+enum Verdict<'a> {
+    Ok,
+    Violations(Vec<Violation<'a>>),
+}
+
+impl Verdict<'_> {
+    fn is_ok(&self) -> bool {
+        *self == Verdict::Ok
+    }
+}
+
+impl<'a> Bag<'a> {
+    /// Returns how many cubes of a color can be shown at once, None meaning no limit
+    fn allowance(&self, color: &str) -> Option<u32> {
+        match self.limits.get(color) {
+            Some(limit) => Some(*limit),
+            None => match self.unknown_colors {
+                ColorPolicy::Allow => None,
+                ColorPolicy::Reject => Some(0),
+            },
+        }
+    }
+
+    fn judge(&self, used_by_color: &HashMap<&'a str, u32>) -> Verdict<'a> {
+        let mut violations = Vec::new();
+
+        // colors shown by the game, unknown ones included
+        for (color, observed) in used_by_color.iter() {
+            let allowed = self.limits.get(color).copied();
+
+            let is_violating = match allowed {
+                Some(allowed) => *observed > allowed,
+                None => self.unknown_colors == ColorPolicy::Reject,
+            };
+
+            if is_violating {
+                violations.push(Violation {
+                    color,
+                    observed: Some(*observed),
+                    allowed,
+                });
+            }
+        }
+
+        // colors of the bag the game never showed
+        if self.unseen_colors == ColorPolicy::Reject {
+            for (color, allowed) in self.limits.iter() {
+                if !used_by_color.contains_key(color) {
+                    violations.push(Violation {
+                        color,
+                        observed: None,
+                        allowed: Some(*allowed),
+                    });
                 }
-                None => return false,
             }
         }
 
-        true
+        if violations.is_empty() {
+            Verdict::Ok
+        } else {
+            violations.sort_by_key(|violation| violation.color);
+            Verdict::Violations(violations)
+        }
     }
+}
 
-    fn minimum_set_power(&self) -> u32 {
-        self.max_used_by_color
-            .values()
-            .fold(1u32, |power, used_color| power * used_color)
+fn default_bag() -> Bag<'static> {
+    let mut limits = HashMap::new();
+    limits.insert("red", 12);
+    limits.insert("green", 13);
+    limits.insert("blue", 14);
+
+    Bag {
+        limits,
+        unseen_colors: ColorPolicy::Allow,
+        unknown_colors: ColorPolicy::Reject,
     }
 }
 
-fn ex1(games: &Vec<Game>) -> i32 {
-    let max_cubes: HashMap<&str, u32> = {
-        let mut m = HashMap::new();
-        m.insert("red", 12);
-        m.insert("green", 13);
-        m.insert("blue", 14);
-        m
-    };
+fn ex1(games: &[Game]) -> i32 {
+    let bag = default_bag();
 
-    let invalid_games = games
+    games
         .iter()
-        .filter(|game| game.validates(&max_cubes))
+        .filter(|game| game.validates(&bag).is_ok())
         .map(|game| game.id)
-        .sum();
-
-    invalid_games
+        .sum()
 }
 
-fn ex2(games: &Vec<Game>) -> u32 {
+fn ex2(games: &[Game]) -> u32 {
     games.iter().map(|game| game.minimum_set_power()).sum()
 }
 
+fn print_reveals(games: &[Game]) {
+    let bag = default_bag();
+
+    for game in games.iter() {
+        println!("{}", game);
+        println!(
+            "  totals: {:?}, fewest: {}",
+            game.reveal_totals(),
+            game.fewest_cubes_in_a_reveal().unwrap_or(0)
+        );
+
+        if let Some((reveal_index, reveal)) = game.breaking_reveal(&bag) {
+            println!("  reveal {} breaks the bag: {}", reveal_index + 1, reveal);
+        }
+    }
+}
+
+fn print_verdicts(games: &[Game], flags: &[String]) {
+    let mut bag = default_bag();
+
+    for flag in flags.iter() {
+        match flag.as_str() {
+            "--reject-unseen" => bag.unseen_colors = ColorPolicy::Reject,
+            "--allow-unknown" => bag.unknown_colors = ColorPolicy::Allow,
+            _ => panic!("Unknown validation flag '{}'", flag),
+        }
+    }
+
+    for game in games.iter() {
+        match game.validates(&bag) {
+            Verdict::Ok => println!("Game {}: ok", game.id),
+            Verdict::Violations(violations) => {
+                println!("Game {}: impossible", game.id);
+                for violation in violations.iter() {
+                    println!("  {}", violation);
+                }
+            }
+        }
+    }
+}
+
+fn run_query(games: &[Game], query: &str) {
+    let colors: HashSet<&str> = games
+        .iter()
+        .flat_map(|game| game.max_used_by_color().into_keys())
+        .collect();
+
+    match Query::parse(query, &colors) {
+        Ok(query) => println!("{}", query.run(games)),
+        Err(error) => {
+            eprintln!("Invalid query: {}", error);
+            std::process::exit(1);
+        }
+    }
+}
+
+impl fmt::Display for Bag<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut limits: Vec<(&&str, &u32)> = self.limits.iter().collect();
+        limits.sort();
+
+        let limits: Vec<String> = limits
+            .iter()
+            .map(|(color, amount)| format!("{} {}", amount, color))
+            .collect();
+
+        write!(f, "{}", limits.join(", "))
+    }
+}
+
+fn print_smallest_bag(games: &[Game]) {
+    let bag = inference::smallest_bag(games);
+
+    let impossible_games = games
+        .iter()
+        .filter(|game| !game.validates(&bag).is_ok())
+        .count();
+
+    if impossible_games > 0 {
+        panic!("Smallest bag should make every game possible");
+    }
+
+    println!("{}", bag);
+}
+
+fn print_probabilities(games: &[Game], args: &[String]) {
+    let mut bag = default_bag();
+    let mut model = DrawModel::PutBack;
+    let mut limits = HashMap::new();
+
+    // arguments are either the draw model or the bag content like `red=12`
+    for arg in args.iter() {
+        match arg.split_once('=') {
+            Some((color, amount)) => {
+                let amount: u32 = amount.parse().expect("cube amount should be a number");
+                limits.insert(color, amount);
+            }
+            None if arg == "--keep-out" => model = DrawModel::KeepOut,
+            None => panic!("Unknown probability argument '{}'", arg),
+        }
+    }
+
+    if !limits.is_empty() {
+        bag.limits = limits;
+    }
+
+    for game in games.iter() {
+        println!(
+            "Game {}: {:e}",
+            game.id,
+            inference::game_probability(game, &bag, model)
+        );
+    }
+}
+
 fn main() {
     let input = include_str!("../etc/input");
-    let games: Vec<Game> = input.lines().map(|line| Game::from(line)).collect();
+    let games: Vec<Game> = input.lines().map(Game::from).collect();
 
-    println!("{}", ex1(&games));
-    println!("{}", ex2(&games));
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.first().map(|command| command.as_str()) {
+        Some("reveals") => print_reveals(&games),
+        Some("validate") => print_verdicts(&games, &args[1..]),
+        Some("query") => run_query(&games, &args[1..].join(" ")),
+        Some("infer") => print_smallest_bag(&games),
+        Some("odds") => print_probabilities(&games, &args[1..]),
+        _ => {
+            println!("{}", ex1(&games));
+            println!("{}", ex2(&games));
+        }
+    }
 }
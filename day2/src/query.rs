@@ -0,0 +1,350 @@
+use std::{collections::HashSet, fmt};
+
+use crate::Game;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Number(i64),
+    Comparator(Comparator),
+    OpenParen,
+    CloseParen,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparator {
+    Lower,
+    LowerOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl Comparator {
+    fn compare(&self, left: i64, right: i64) -> bool {
+        match self {
+            Comparator::Lower => left < right,
+            Comparator::LowerOrEqual => left <= right,
+            Comparator::Greater => left > right,
+            Comparator::GreaterOrEqual => left >= right,
+            Comparator::Equal => left == right,
+            Comparator::NotEqual => left != right,
+        }
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+
+    while let Some(&letter) = chars.peek() {
+        if letter.is_whitespace() {
+            chars.next();
+        } else if letter.is_ascii_digit() {
+            let mut number = String::new();
+            while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+                number.push(digit);
+            }
+            let number = number
+                .parse()
+                .map_err(|_| format!("Number '{}' is too big", number))?;
+            tokens.push(Token::Number(number));
+        } else if letter.is_alphabetic() || letter == '_' {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                word.push(c);
+            }
+            tokens.push(Token::Word(word.to_lowercase()));
+        } else {
+            chars.next();
+            let followed_by_equal = chars.next_if_eq(&'=').is_some();
+
+            let token = match (letter, followed_by_equal) {
+                ('(', false) => Token::OpenParen,
+                (')', false) => Token::CloseParen,
+                ('<', false) => Token::Comparator(Comparator::Lower),
+                ('<', true) => Token::Comparator(Comparator::LowerOrEqual),
+                ('>', false) => Token::Comparator(Comparator::Greater),
+                ('>', true) => Token::Comparator(Comparator::GreaterOrEqual),
+                ('=', _) => Token::Comparator(Comparator::Equal),
+                ('!', true) => Token::Comparator(Comparator::NotEqual),
+                _ => return Err(format!("Unexpected character '{}' in query", letter)),
+            };
+            tokens.push(token);
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Field {
+    Id,
+    Power,
+    // maximum amount of cubes of a color shown in a game, 0 if never shown
+    Color(String),
+}
+
+impl Field {
+    fn value(&self, game: &Game) -> i64 {
+        match self {
+            Field::Id => game.id as i64,
+            Field::Power => game.minimum_set_power() as i64,
+            Field::Color(color) => game
+                .max_used_by_color()
+                .get(color.as_str())
+                .copied()
+                .unwrap_or(0) as i64,
+        }
+    }
+}
+
+// words of the grammar, which cannot name a field
+const KEYWORDS: [&str; 8] = ["filter", "count", "sum", "max", "where", "and", "or", "not"];
+
+impl Field {
+    // colors are only accepted when shown in at least one game
+    fn parse(name: &str, colors: &HashSet<&str>) -> Result<Field, String> {
+        match name {
+            "id" => Ok(Field::Id),
+            "power" | "minimum_set_power" => Ok(Field::Power),
+            keyword if KEYWORDS.contains(&keyword) => {
+                Err(format!("Expected a field, got keyword '{}'", keyword))
+            }
+            color if colors.contains(color) => Ok(Field::Color(color.to_string())),
+            unknown => Err(format!("Unknown field or color '{}'", unknown)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Field(Field),
+    Number(i64),
+}
+
+impl Operand {
+    fn value(&self, game: &Game) -> i64 {
+        match self {
+            Operand::Field(field) => field.value(game),
+            Operand::Number(number) => *number,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Compare(Operand, Comparator, Operand),
+    Not(Box<Condition>),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+}
+
+impl Condition {
+    fn matches(&self, game: &Game) -> bool {
+        match self {
+            Condition::Compare(left, comparator, right) => {
+                comparator.compare(left.value(game), right.value(game))
+            }
+            Condition::Not(condition) => !condition.matches(game),
+            Condition::And(left, right) => left.matches(game) && right.matches(game),
+            Condition::Or(left, right) => left.matches(game) || right.matches(game),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operation {
+    Filter,
+    Count,
+    Sum(Field),
+    Max(Field),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    operation: Operation,
+    condition: Option<Condition>,
+}
+
+#[derive(Debug)]
+pub enum QueryResult<'q, 'a> {
+    Games(Vec<&'q Game<'a>>),
+    Number(i64),
+    Nothing,
+}
+
+impl fmt::Display for QueryResult<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryResult::Games(games) => {
+                let lines: Vec<String> = games.iter().map(|game| game.to_string()).collect();
+                write!(f, "{}", lines.join("\n"))
+            }
+            QueryResult::Number(number) => write!(f, "{}", number),
+            QueryResult::Nothing => write!(f, "no matching game"),
+        }
+    }
+}
+
+// grammar, from lowest to highest precedence:
+//   query      := operation [ "where" or ]
+//   operation  := "filter" | "count" | "sum" field | "max" field
+//   or         := and { "or" and }
+//   and        := not { "and" not }
+//   not        := "not" not | "(" or ")" | comparison
+//   comparison := operand comparator operand
+//   operand    := number | field
+struct Parser<'c> {
+    tokens: Vec<Token>,
+    position: usize,
+    colors: &'c HashSet<&'c str>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn next_is_word(&mut self, word: &str) -> bool {
+        if self.peek() == Some(&Token::Word(word.to_string())) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn field(&mut self) -> Result<Field, String> {
+        match self.next() {
+            Some(Token::Word(name)) => Field::parse(&name, self.colors),
+            token => Err(format!("Expected a field, got {:?}", token)),
+        }
+    }
+
+    fn operation(&mut self) -> Result<Operation, String> {
+        match self.next() {
+            Some(Token::Word(word)) => match word.as_str() {
+                "filter" => Ok(Operation::Filter),
+                "count" => Ok(Operation::Count),
+                "sum" => Ok(Operation::Sum(self.field()?)),
+                "max" => Ok(Operation::Max(self.field()?)),
+                _ => Err(format!("Unknown operation '{}'", word)),
+            },
+            token => Err(format!("Expected an operation, got {:?}", token)),
+        }
+    }
+
+    fn or(&mut self) -> Result<Condition, String> {
+        let mut condition = self.and()?;
+
+        while self.next_is_word("or") {
+            condition = Condition::Or(Box::new(condition), Box::new(self.and()?));
+        }
+
+        Ok(condition)
+    }
+
+    fn and(&mut self) -> Result<Condition, String> {
+        let mut condition = self.not()?;
+
+        while self.next_is_word("and") {
+            condition = Condition::And(Box::new(condition), Box::new(self.not()?));
+        }
+
+        Ok(condition)
+    }
+
+    fn not(&mut self) -> Result<Condition, String> {
+        if self.next_is_word("not") {
+            return Ok(Condition::Not(Box::new(self.not()?)));
+        }
+
+        if self.peek() == Some(&Token::OpenParen) {
+            self.position += 1;
+            let condition = self.or()?;
+
+            return match self.next() {
+                Some(Token::CloseParen) => Ok(condition),
+                token => Err(format!("Expected ')', got {:?}", token)),
+            };
+        }
+
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Condition, String> {
+        let left = self.operand()?;
+
+        let comparator = match self.next() {
+            Some(Token::Comparator(comparator)) => comparator,
+            token => return Err(format!("Expected a comparator, got {:?}", token)),
+        };
+
+        let right = self.operand()?;
+
+        Ok(Condition::Compare(left, comparator, right))
+    }
+
+    fn operand(&mut self) -> Result<Operand, String> {
+        match self.next() {
+            Some(Token::Number(number)) => Ok(Operand::Number(number)),
+            Some(Token::Word(name)) => Ok(Operand::Field(Field::parse(&name, self.colors)?)),
+            token => Err(format!("Expected a field or a number, got {:?}", token)),
+        }
+    }
+}
+
+impl Query {
+    /// Parses a query, whose colors should be among the ones known
+    pub fn parse(query: &str, colors: &HashSet<&str>) -> Result<Query, String> {
+        let mut parser = Parser {
+            tokens: tokenize(query)?,
+            position: 0,
+            colors,
+        };
+
+        let operation = parser.operation()?;
+
+        let condition = if parser.next_is_word("where") {
+            Some(parser.or()?)
+        } else {
+            None
+        };
+
+        if let Some(token) = parser.peek() {
+            return Err(format!("Unexpected {:?} at the end of the query", token));
+        }
+
+        Ok(Query {
+            operation,
+            condition,
+        })
+    }
+
+    pub fn run<'q, 'a>(&self, games: &'q [Game<'a>]) -> QueryResult<'q, 'a> {
+        let matching_games = games.iter().filter(|game| match &self.condition {
+            Some(condition) => condition.matches(game),
+            None => true,
+        });
+
+        match &self.operation {
+            Operation::Filter => QueryResult::Games(matching_games.collect()),
+            Operation::Count => QueryResult::Number(matching_games.count() as i64),
+            Operation::Sum(field) => {
+                QueryResult::Number(matching_games.map(|game| field.value(game)).sum())
+            }
+            Operation::Max(field) => match matching_games.map(|game| field.value(game)).max() {
+                Some(max) => QueryResult::Number(max),
+                None => QueryResult::Nothing,
+            },
+        }
+    }
+}
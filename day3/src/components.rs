@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+
+use crate::schematic::Schematic;
+
+// a node of the schematic graph, numbers and symbols being linked when they touch
+#[derive(Debug, Clone, Copy)]
+enum Node {
+    Number(usize),
+    Symbol(usize),
+}
+
+/// Numbers and symbols transitively touching each other
+#[derive(Debug, Default)]
+pub struct Component {
+    pub number_indexes: Vec<usize>,
+    pub symbol_indexes: Vec<usize>,
+}
+
+impl Component {
+    pub fn size(&self) -> usize {
+        self.number_indexes.len() + self.symbol_indexes.len()
+    }
+
+    pub fn sum(&self, schematic: &Schematic) -> u32 {
+        self.number_indexes
+            .iter()
+            .map(|number_index| schematic.numbers[*number_index].value)
+            .sum()
+    }
+
+    /// Counts the symbols of the component by character
+    pub fn symbol_mix(&self, schematic: &Schematic) -> BTreeMap<char, usize> {
+        let mut mix = BTreeMap::new();
+
+        for symbol_index in self.symbol_indexes.iter() {
+            *mix.entry(schematic.symbols[*symbol_index].char)
+                .or_insert(0) += 1;
+        }
+
+        mix
+    }
+}
+
+/// Groups numbers and symbols into connected components, isolated numbers included
+pub fn components(schematic: &Schematic) -> Vec<Component> {
+    let mut visited_numbers = vec![false; schematic.numbers.len()];
+    let mut visited_symbols = vec![false; schematic.symbols.len()];
+    let mut components = Vec::new();
+
+    let starts = (0..schematic.numbers.len())
+        .map(Node::Number)
+        .chain((0..schematic.symbols.len()).map(Node::Symbol));
+
+    for start in starts {
+        let already_visited = match start {
+            Node::Number(number_index) => visited_numbers[number_index],
+            Node::Symbol(symbol_index) => visited_symbols[symbol_index],
+        };
+
+        if already_visited {
+            continue;
+        }
+
+        let mut component = Component::default();
+        let mut to_visit = vec![start];
+
+        while let Some(node) = to_visit.pop() {
+            match node {
+                Node::Number(number_index) => {
+                    if visited_numbers[number_index] {
+                        continue;
+                    }
+                    visited_numbers[number_index] = true;
+                    component.number_indexes.push(number_index);
+
+                    to_visit.extend(
+                        schematic
+                            .symbol_indexes_touching(number_index)
+                            .iter()
+                            .map(|symbol_index| Node::Symbol(*symbol_index)),
+                    );
+                }
+                Node::Symbol(symbol_index) => {
+                    if visited_symbols[symbol_index] {
+                        continue;
+                    }
+                    visited_symbols[symbol_index] = true;
+                    component.symbol_indexes.push(symbol_index);
+
+                    to_visit.extend(
+                        schematic
+                            .number_indexes_touching(symbol_index)
+                            .iter()
+                            .map(|number_index| Node::Number(*number_index)),
+                    );
+                }
+            }
+        }
+
+        component.number_indexes.sort();
+        component.symbol_indexes.sort();
+        components.push(component);
+    }
+
+    components
+}
@@ -0,0 +1,184 @@
+use std::fmt;
+
+use crate::schematic::Schematic;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Adjacency {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Adjacency {
+    fn accepts(&self, count: usize) -> bool {
+        match self {
+            Adjacency::Exactly(expected) => count == *expected,
+            Adjacency::AtLeast(minimum) => count >= *minimum,
+        }
+    }
+
+    // the count of numbers a gear is expected to touch
+    fn count(&self) -> usize {
+        match self {
+            Adjacency::Exactly(count) | Adjacency::AtLeast(count) => *count,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GearRule {
+    // symbols that can be gears
+    pub symbols: Vec<char>,
+    // how many numbers a symbol must touch to be a gear
+    pub adjacency: Adjacency,
+}
+
+impl Default for GearRule {
+    fn default() -> Self {
+        GearRule {
+            symbols: vec!['*'],
+            adjacency: Adjacency::AtLeast(2),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Gear {
+    pub symbol_index: usize,
+    pub ratio: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Anomaly {
+    // a gear symbol touching more numbers than the rule counts
+    CrowdedSymbol {
+        symbol_index: usize,
+        number_indexes: Vec<usize>,
+    },
+    // a number touching several gear symbols
+    SharedNumber {
+        number_index: usize,
+        symbol_indexes: Vec<usize>,
+    },
+}
+
+pub struct AnomalyReport<'s> {
+    schematic: &'s Schematic,
+    anomaly: &'s Anomaly,
+}
+
+impl fmt::Display for AnomalyReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.anomaly {
+            Anomaly::CrowdedSymbol {
+                symbol_index,
+                number_indexes,
+            } => {
+                let symbol = &self.schematic.symbols[*symbol_index];
+                let values: Vec<String> = number_indexes
+                    .iter()
+                    .map(|number_index| self.schematic.numbers[*number_index].value.to_string())
+                    .collect();
+
+                write!(
+                    f,
+                    "'{}' at ({}, {}) touches {} numbers: {}",
+                    symbol.char,
+                    symbol.col,
+                    symbol.row,
+                    number_indexes.len(),
+                    values.join(", ")
+                )
+            }
+            Anomaly::SharedNumber {
+                number_index,
+                symbol_indexes,
+            } => {
+                let number = &self.schematic.numbers[*number_index];
+                let positions: Vec<String> = symbol_indexes
+                    .iter()
+                    .map(|symbol_index| &self.schematic.symbols[*symbol_index])
+                    .map(|symbol| format!("'{}' at ({}, {})", symbol.char, symbol.col, symbol.row))
+                    .collect();
+
+                write!(
+                    f,
+                    "{} at ({}, {}) touches {} gear symbols: {}",
+                    number.value,
+                    number.col_span.start(),
+                    number.row,
+                    symbol_indexes.len(),
+                    positions.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl Anomaly {
+    pub fn report<'s>(&'s self, schematic: &'s Schematic) -> AnomalyReport<'s> {
+        AnomalyReport {
+            schematic,
+            anomaly: self,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GearScan {
+    pub gears: Vec<Gear>,
+    pub anomalies: Vec<Anomaly>,
+}
+
+impl GearRule {
+    fn is_gear_symbol(&self, schematic: &Schematic, symbol_index: usize) -> bool {
+        self.symbols.contains(&schematic.symbols[symbol_index].char)
+    }
+
+    pub fn scan(&self, schematic: &Schematic) -> GearScan {
+        let mut gears = Vec::new();
+        let mut anomalies = Vec::new();
+
+        for symbol_index in 0..schematic.symbols.len() {
+            if !self.is_gear_symbol(schematic, symbol_index) {
+                continue;
+            }
+
+            let number_indexes = schematic.number_indexes_touching(symbol_index);
+
+            if self.adjacency.accepts(number_indexes.len()) {
+                gears.push(Gear {
+                    symbol_index,
+                    ratio: number_indexes
+                        .iter()
+                        .map(|number_index| schematic.numbers[*number_index].value as u64)
+                        .product(),
+                });
+            }
+
+            if number_indexes.len() > self.adjacency.count() {
+                anomalies.push(Anomaly::CrowdedSymbol {
+                    symbol_index,
+                    number_indexes: number_indexes.to_vec(),
+                });
+            }
+        }
+
+        for number_index in 0..schematic.numbers.len() {
+            let symbol_indexes: Vec<usize> = schematic
+                .symbol_indexes_touching(number_index)
+                .iter()
+                .copied()
+                .filter(|symbol_index| self.is_gear_symbol(schematic, *symbol_index))
+                .collect();
+
+            if symbol_indexes.len() > 1 {
+                anomalies.push(Anomaly::SharedNumber {
+                    number_index,
+                    symbol_indexes,
+                });
+            }
+        }
+
+        GearScan { gears, anomalies }
+    }
+}
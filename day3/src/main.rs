@@ -1,177 +1,157 @@
-use std::{collections::HashMap, ops::RangeInclusive};
-
-use regex::Regex;
-
-struct Grid {
-    data: Vec<Vec<char>>,
+use components::components;
+use gears::{Adjacency, GearRule};
+use grid::Grid;
+use render::{Rendering, Window};
+use schematic::Schematic;
+
+mod components;
+mod gears;
+mod render;
+mod schematic;
+
+fn ex1(schematic: &Schematic) -> u32 {
+    schematic.part_numbers().map(|number| number.value).sum()
 }
 
-impl Grid {
-    fn get_at(&self, col_index: usize, row_index: usize) -> Option<&char> {
-        self.data
-            .get(row_index)
-            .map(|row| row.get(col_index))
-            .flatten()
-    }
-
-    fn near_symbol(&self, col_start: usize, col_end: usize, row_index: usize) -> bool {
-        let row_range = RangeInclusive::new(
-            if row_index == 0 {
-                row_index
-            } else {
-                row_index - 1
-            },
-            row_index + 1,
-        );
+fn ex2(schematic: &Schematic) -> u64 {
+    GearRule::default()
+        .scan(schematic)
+        .gears
+        .iter()
+        .map(|gear| gear.ratio)
+        .sum()
+}
 
-        for row in row_range {
-            let col_range = RangeInclusive::new(
-                if col_start == 0 {
-                    col_start
-                } else {
-                    col_start - 1
-                },
-                col_end + 1,
-            );
-
-            for col in col_range {
-                let have_found_symbol = self
-                    .get_at(col, row)
-                    .map(|char| !char.is_ascii_digit() && *char != '.')
-                    .unwrap_or(false);
-
-                if have_found_symbol {
-                    return true;
-                }
+// builds a gear rule from arguments like `--symbols *# --exactly 2`
+fn parse_gear_rule(args: &[String]) -> GearRule {
+    let mut rule = GearRule::default();
+    let mut args = args.iter();
+
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .unwrap_or_else(|| panic!("Flag '{}' should have a value", flag));
+
+        match flag.as_str() {
+            "--symbols" => rule.symbols = value.chars().collect(),
+            "--exactly" => {
+                rule.adjacency =
+                    Adjacency::Exactly(value.parse().expect("Count should be a number"))
             }
-        }
-
-        false
-    }
-
-    fn surrounding_stars(
-        &self,
-        col_start: usize,
-        col_end: usize,
-        row_index: usize,
-    ) -> Vec<(usize, usize)> {
-        let mut surrounding_stars: Vec<(usize, usize)> = Vec::new();
-        let row_range = RangeInclusive::new(
-            if row_index == 0 {
-                row_index
-            } else {
-                row_index - 1
-            },
-            row_index + 1,
-        );
-
-        for row in row_range {
-            let col_range = RangeInclusive::new(
-                if col_start == 0 {
-                    col_start
-                } else {
-                    col_start - 1
-                },
-                col_end + 1,
-            );
-
-            for col in col_range {
-                let have_found_star = self
-                    .get_at(col, row)
-                    .map(|char| *char == '*')
-                    .unwrap_or(false);
-
-                if have_found_star {
-                    surrounding_stars.push((col, row));
-                }
+            "--at-least" => {
+                rule.adjacency =
+                    Adjacency::AtLeast(value.parse().expect("Count should be a number"))
             }
+            _ => panic!("Unknown gear flag '{}'", flag),
         }
-
-        surrounding_stars
     }
-}
-
-impl From<&str> for Grid {
-    fn from(input: &str) -> Self {
-        let data = input.lines().map(|line| line.chars().collect()).collect();
 
-        Grid { data }
-    }
+    rule
 }
 
-fn ex1(input: &str) -> u32 {
-    let number_re = Regex::new(r"((\d+)\D*)").unwrap();
-    let grid: Grid = input.into();
+fn print_gears(schematic: &Schematic, args: &[String]) {
+    let scan = parse_gear_rule(args).scan(schematic);
 
-    let mut sum = 0;
+    for gear in scan.gears.iter() {
+        let symbol = &schematic.symbols[gear.symbol_index];
+        println!(
+            "'{}' at ({}, {}): ratio {}",
+            symbol.char, symbol.col, symbol.row, gear.ratio
+        );
+    }
 
-    for (row_index, row) in input.lines().enumerate() {
-        for capture in number_re.captures_iter(row) {
-            let number_match = capture
-                .get(2)
-                .expect("Should have captured a number with regex");
+    let total_ratio: u64 = scan.gears.iter().map(|gear| gear.ratio).sum();
+    println!("{} gears, total ratio {}", scan.gears.len(), total_ratio);
 
-            if grid.near_symbol(number_match.start(), number_match.end() - 1, row_index) {
-                sum += number_match
-                    .as_str()
-                    .parse::<u32>()
-                    .expect("Number should be a number");
-            }
-        }
+    for anomaly in scan.anomalies.iter() {
+        println!("anomaly: {}", anomaly.report(schematic));
     }
-
-    sum
 }
 
-fn ex2(input: &str) -> u32 {
-    let number_re = Regex::new(r"((\d+)\D*)").unwrap();
-    let grid: Grid = input.into();
-
-    let mut numbers_by_gears: HashMap<(usize, usize), Vec<u32>> = HashMap::new();
-
-    // indexing all the part numbers by star coordinates
-    for (row_index, row) in input.lines().enumerate() {
-        for capture in number_re.captures_iter(row) {
-            let number_match = capture
-                .get(2)
-                .expect("Should have captured a number with regex");
-            let surrounding_stars =
-                grid.surrounding_stars(number_match.start(), number_match.end() - 1, row_index);
-
-            let part_number = number_match
-                .as_str()
-                .parse::<u32>()
-                .expect("Number should be a number");
-
-            for surrounding_star_coordinate in surrounding_stars {
-                numbers_by_gears
-                    .entry(surrounding_star_coordinate)
-                    .and_modify(|related_part_numbers| related_part_numbers.push(part_number))
-                    .or_insert(vec![part_number]);
+// renders the schematic in a format, optionally cropped with `--around col,row --radius n`
+fn print_rendering(grid: &Grid<char>, schematic: &Schematic, args: &[String]) {
+    let format = args.first().map(|format| format.as_str()).unwrap_or("ansi");
+
+    let mut center = None;
+    let mut radius = 5;
+    let mut flags = args.iter().skip(1);
+
+    while let Some(flag) = flags.next() {
+        let value = flags
+            .next()
+            .unwrap_or_else(|| panic!("Flag '{}' should have a value", flag));
+
+        match flag.as_str() {
+            "--around" => {
+                let (col, row) = value
+                    .split_once(',')
+                    .expect("Center should be written as col,row");
+                center = Some((
+                    col.parse().expect("Column should be a number"),
+                    row.parse().expect("Row should be a number"),
+                ));
             }
+            "--radius" => radius = value.parse().expect("Radius should be a number"),
+            _ => panic!("Unknown render flag '{}'", flag),
         }
     }
 
-    // unrolling stars to multiply gear ratios
-    let mut gear_ratios = 0;
+    let window = match center {
+        Some(center) => Window::around(center, radius),
+        None => Window::whole(grid),
+    };
 
-    for (_, part_numbers) in numbers_by_gears.iter() {
-        if part_numbers.len() > 1 {
-            gear_ratios += part_numbers
-                .iter()
-                .fold(1, |accumulator, part_number| accumulator * part_number);
-        }
-        if part_numbers.len() > 2 {
-            println!("Something is up");
-        }
+    let scan = GearRule::default().scan(schematic);
+    let rendering = Rendering::new(grid, schematic, &scan, window);
+
+    match format {
+        "ansi" => print!("{}", rendering.ansi()),
+        "svg" => print!("{}", rendering.svg()),
+        "html" => print!("{}", rendering.html()),
+        _ => panic!("Unknown render format '{}'", format),
     }
+}
 
-    gear_ratios
+fn print_components(schematic: &Schematic) {
+    let mut components = components(schematic);
+
+    // biggest components first
+    components.sort_by_key(|component| std::cmp::Reverse(component.size()));
+
+    for component in components.iter() {
+        let mix: Vec<String> = component
+            .symbol_mix(schematic)
+            .iter()
+            .map(|(symbol, count)| format!("{} x{}", symbol, count))
+            .collect();
+
+        println!(
+            "size {} ({} numbers, {} symbols), sum {}, symbols: [{}]",
+            component.size(),
+            component.number_indexes.len(),
+            component.symbol_indexes.len(),
+            component.sum(schematic),
+            mix.join(", ")
+        );
+    }
 }
 
 fn main() {
     let input = include_str!("../etc/input");
-
-    println!("{}", ex1(input));
-    println!("{}", ex2(input));
+    let grid: Grid<char> = input
+        .parse()
+        .expect("Input should be a rectangular schematic");
+    let schematic = Schematic::from(&grid);
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.first().map(|command| command.as_str()) {
+        Some("gears") => print_gears(&schematic, &args[1..]),
+        Some("components") => print_components(&schematic),
+        Some("render") => print_rendering(&grid, &schematic, &args[1..]),
+        _ => {
+            println!("{}", ex1(&schematic));
+            println!("{}", ex2(&schematic));
+        }
+    }
 }
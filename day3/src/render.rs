@@ -0,0 +1,285 @@
+use std::{collections::HashMap, fmt::Write, ops::RangeInclusive};
+
+use grid::{Grid, Position};
+
+use crate::{gears::GearScan, schematic::Schematic};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CellKind {
+    Empty,
+    // digit of a number touching a symbol
+    PartNumber,
+    // digit of a number touching no symbol
+    UncountedNumber,
+    Symbol,
+    Gear,
+}
+
+impl CellKind {
+    fn ansi_color(&self) -> &'static str {
+        match self {
+            CellKind::Empty => "\x1b[2m",
+            CellKind::PartNumber => "\x1b[32m",
+            CellKind::UncountedNumber => "\x1b[31m",
+            CellKind::Symbol => "\x1b[33m",
+            CellKind::Gear => "\x1b[1;35m",
+        }
+    }
+
+    fn css_class(&self) -> &'static str {
+        match self {
+            CellKind::Empty => "empty",
+            CellKind::PartNumber => "part",
+            CellKind::UncountedNumber => "uncounted",
+            CellKind::Symbol => "symbol",
+            CellKind::Gear => "gear",
+        }
+    }
+}
+
+const STYLE: &str = ".empty { fill: #bbb; color: #bbb; } \
+.part { fill: #2a2; color: #2a2; } \
+.uncounted { fill: #d22; color: #d22; } \
+.symbol { fill: #c90; color: #c90; } \
+.gear { fill: #a2a; color: #a2a; font-weight: bold; }";
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+// size of a character in the SVG output
+const CELL_WIDTH: usize = 10;
+const CELL_HEIGHT: usize = 16;
+
+#[derive(Debug, Clone)]
+pub struct Window {
+    cols: RangeInclusive<usize>,
+    rows: RangeInclusive<usize>,
+}
+
+impl Window {
+    pub fn whole<T>(grid: &Grid<T>) -> Window {
+        Window {
+            cols: 0..=grid.width().saturating_sub(1),
+            rows: 0..=grid.height().saturating_sub(1),
+        }
+    }
+
+    pub fn around((col, row): Position, radius: usize) -> Window {
+        Window {
+            cols: col.saturating_sub(radius)..=col + radius,
+            rows: row.saturating_sub(radius)..=row + radius,
+        }
+    }
+}
+
+pub struct Rendering<'g> {
+    grid: &'g Grid<char>,
+    kinds: Grid<CellKind>,
+    gear_ratios: HashMap<Position, u64>,
+    window: Window,
+}
+
+impl<'g> Rendering<'g> {
+    pub fn new(
+        grid: &'g Grid<char>,
+        schematic: &Schematic,
+        scan: &GearScan,
+        window: Window,
+    ) -> Rendering<'g> {
+        let mut kinds = Grid::filled(grid.width(), grid.height(), CellKind::Empty);
+
+        for (number_index, number) in schematic.numbers.iter().enumerate() {
+            let kind = if schematic.symbol_indexes_touching(number_index).is_empty() {
+                CellKind::UncountedNumber
+            } else {
+                CellKind::PartNumber
+            };
+
+            for col in number.col_span.clone() {
+                kinds.set((col, number.row), kind);
+            }
+        }
+
+        for symbol in schematic.symbols.iter() {
+            kinds.set((symbol.col, symbol.row), CellKind::Symbol);
+        }
+
+        let mut gear_ratios = HashMap::new();
+
+        for gear in scan.gears.iter() {
+            let symbol = &schematic.symbols[gear.symbol_index];
+            kinds.set((symbol.col, symbol.row), CellKind::Gear);
+            gear_ratios.insert((symbol.col, symbol.row), gear.ratio);
+        }
+
+        Rendering {
+            grid,
+            kinds,
+            gear_ratios,
+            window,
+        }
+    }
+
+    // rows of the window that exist in the grid, with their cells
+    fn visible_rows(&self) -> impl Iterator<Item = Vec<(Position, char, CellKind)>> + '_ {
+        self.window
+            .rows
+            .clone()
+            .map(move |row| {
+                self.window
+                    .cols
+                    .clone()
+                    .filter_map(|col| {
+                        let letter = self.grid.get((col, row))?;
+                        let kind = self.kinds.get((col, row))?;
+                        Some(((col, row), *letter, *kind))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .filter(|cells| !cells.is_empty())
+    }
+
+    fn visible_gear_ratios(&self) -> Vec<(Position, u64)> {
+        let mut gear_ratios: Vec<(Position, u64)> = self
+            .gear_ratios
+            .iter()
+            .filter(|((col, row), _)| {
+                self.window.cols.contains(col) && self.window.rows.contains(row)
+            })
+            .map(|(position, ratio)| (*position, *ratio))
+            .collect();
+
+        gear_ratios.sort_by_key(|((col, row), _)| (*row, *col));
+        gear_ratios
+    }
+
+    /// Renders the schematic with ANSI colors, followed by the ratios of the gears shown
+    pub fn ansi(&self) -> String {
+        let mut output = String::new();
+
+        for cells in self.visible_rows() {
+            for (_, letter, kind) in cells {
+                write!(output, "{}{}{}", kind.ansi_color(), letter, ANSI_RESET).unwrap();
+            }
+            output.push('\n');
+        }
+
+        for ((col, row), ratio) in self.visible_gear_ratios() {
+            writeln!(
+                output,
+                "{}gear at ({}, {}){}: ratio {}",
+                CellKind::Gear.ansi_color(),
+                col,
+                row,
+                ANSI_RESET,
+                ratio
+            )
+            .unwrap();
+        }
+
+        output
+    }
+
+    pub fn svg(&self) -> String {
+        let rows: Vec<Vec<(Position, char, CellKind)>> = self.visible_rows().collect();
+        let width = rows.first().map(|cells| cells.len()).unwrap_or(0) * CELL_WIDTH;
+        let height = rows.len() * CELL_HEIGHT;
+
+        let mut output = String::new();
+
+        writeln!(
+            output,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" font-family="monospace" font-size="{}">"#,
+            width, height, CELL_HEIGHT - 2
+        )
+        .unwrap();
+        writeln!(output, "<style>{}</style>", STYLE).unwrap();
+
+        for (row_offset, cells) in rows.iter().enumerate() {
+            for (col_offset, (position, letter, kind)) in cells.iter().enumerate() {
+                let x = col_offset * CELL_WIDTH;
+                let y = (row_offset + 1) * CELL_HEIGHT - 4;
+
+                match self.gear_ratios.get(position) {
+                    Some(ratio) => writeln!(
+                        output,
+                        r#"<text x="{}" y="{}" class="{}"><title>ratio {}</title>{}</text>"#,
+                        x,
+                        y,
+                        kind.css_class(),
+                        ratio,
+                        escape(*letter)
+                    ),
+                    None => writeln!(
+                        output,
+                        r#"<text x="{}" y="{}" class="{}">{}</text>"#,
+                        x,
+                        y,
+                        kind.css_class(),
+                        escape(*letter)
+                    ),
+                }
+                .unwrap();
+            }
+        }
+
+        output.push_str("</svg>\n");
+        output
+    }
+
+    pub fn html(&self) -> String {
+        let mut output = String::new();
+
+        writeln!(output, "<!DOCTYPE html>").unwrap();
+        writeln!(
+            output,
+            "<html><head><style>{}</style></head><body><pre>",
+            STYLE
+        )
+        .unwrap();
+
+        for cells in self.visible_rows() {
+            for (position, letter, kind) in cells {
+                match self.gear_ratios.get(&position) {
+                    Some(ratio) => write!(
+                        output,
+                        r#"<span class="{}" title="ratio {}">{}</span>"#,
+                        kind.css_class(),
+                        ratio,
+                        escape(letter)
+                    ),
+                    None => write!(
+                        output,
+                        r#"<span class="{}">{}</span>"#,
+                        kind.css_class(),
+                        escape(letter)
+                    ),
+                }
+                .unwrap();
+            }
+            output.push('\n');
+        }
+
+        writeln!(output, "</pre><ul>").unwrap();
+        for ((col, row), ratio) in self.visible_gear_ratios() {
+            writeln!(
+                output,
+                "<li>gear at ({}, {}): ratio {}</li>",
+                col, row, ratio
+            )
+            .unwrap();
+        }
+        writeln!(output, "</ul></body></html>").unwrap();
+
+        output
+    }
+}
+
+fn escape(letter: char) -> String {
+    match letter {
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '&' => "&amp;".to_string(),
+        '"' => "&quot;".to_string(),
+        _ => letter.to_string(),
+    }
+}
@@ -0,0 +1,145 @@
+use std::{collections::HashMap, ops::RangeInclusive};
+
+use grid::{Grid, Position};
+
+#[derive(Debug)]
+pub struct PartNumber {
+    pub value: u32,
+    pub row: usize,
+    pub col_span: RangeInclusive<usize>,
+}
+
+impl PartNumber {
+    // cells around the number, the number itself included
+    fn surroundings<'g>(&self, grid: &'g Grid<char>) -> impl Iterator<Item = (Position, &'g char)> {
+        grid.region(
+            self.col_span.start().saturating_sub(1)..=self.col_span.end() + 1,
+            self.row.saturating_sub(1)..=self.row + 1,
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct Symbol {
+    pub char: char,
+    pub row: usize,
+    pub col: usize,
+}
+
+fn is_symbol(cell: &char) -> bool {
+    !cell.is_ascii_digit() && *cell != '.'
+}
+
+#[derive(Debug)]
+pub struct Schematic {
+    pub numbers: Vec<PartNumber>,
+    pub symbols: Vec<Symbol>,
+    // indexes of the symbols touching each number, and the other way around
+    symbols_by_number: Vec<Vec<usize>>,
+    numbers_by_symbol: Vec<Vec<usize>>,
+}
+
+impl From<&Grid<char>> for Schematic {
+    fn from(grid: &Grid<char>) -> Self {
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+
+        for (row, cells) in grid.rows().enumerate() {
+            // number being read, with the column it started at
+            let mut current_number: Option<(u32, usize)> = None;
+
+            for (col, cell) in cells.iter().enumerate() {
+                if let Some(digit) = cell.to_digit(10) {
+                    current_number = match current_number {
+                        Some((value, start)) => Some((value * 10 + digit, start)),
+                        None => Some((digit, col)),
+                    };
+                    continue;
+                }
+
+                if let Some((value, start)) = current_number.take() {
+                    numbers.push(PartNumber {
+                        value,
+                        row,
+                        col_span: start..=col - 1,
+                    });
+                }
+
+                if is_symbol(cell) {
+                    symbols.push(Symbol {
+                        char: *cell,
+                        row,
+                        col,
+                    });
+                }
+            }
+
+            // number written until the end of the row
+            if let Some((value, start)) = current_number {
+                numbers.push(PartNumber {
+                    value,
+                    row,
+                    col_span: start..=cells.len() - 1,
+                });
+            }
+        }
+
+        let symbol_index_by_position: HashMap<Position, usize> = symbols
+            .iter()
+            .enumerate()
+            .map(|(index, symbol)| ((symbol.col, symbol.row), index))
+            .collect();
+
+        let mut numbers_by_symbol = vec![Vec::new(); symbols.len()];
+
+        let symbols_by_number: Vec<Vec<usize>> = numbers
+            .iter()
+            .enumerate()
+            .map(|(number_index, number)| {
+                let touched_symbols: Vec<usize> = number
+                    .surroundings(grid)
+                    .filter_map(|(position, _)| symbol_index_by_position.get(&position))
+                    .copied()
+                    .collect();
+
+                for symbol_index in touched_symbols.iter() {
+                    numbers_by_symbol[*symbol_index].push(number_index);
+                }
+
+                touched_symbols
+            })
+            .collect();
+
+        Schematic {
+            numbers,
+            symbols,
+            symbols_by_number,
+            numbers_by_symbol,
+        }
+    }
+}
+
+impl Schematic {
+    pub fn symbols_touching(&self, number_index: usize) -> impl Iterator<Item = &Symbol> {
+        self.symbol_indexes_touching(number_index)
+            .iter()
+            .map(|symbol_index| &self.symbols[*symbol_index])
+    }
+
+    pub fn symbol_indexes_touching(&self, number_index: usize) -> &[usize] {
+        &self.symbols_by_number[number_index]
+    }
+
+    pub fn number_indexes_touching(&self, symbol_index: usize) -> &[usize] {
+        &self.numbers_by_symbol[symbol_index]
+    }
+
+    /// Numbers touching at least one symbol
+    pub fn part_numbers(&self) -> impl Iterator<Item = &PartNumber> {
+        self.numbers
+            .iter()
+            .enumerate()
+            .filter(|(number_index, _)| self.symbols_touching(*number_index).next().is_some())
+            .map(|(_, number)| number)
+    }
+}
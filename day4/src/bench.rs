@@ -0,0 +1,86 @@
+use std::{collections::HashSet, fmt::Write, hint::black_box, time::Instant};
+
+use crate::{parse_cards, Card};
+
+// each card is matched this many times so timings are not lost in the noise
+const ROUNDS: usize = 20;
+
+// xorshift generator, good enough to spread numbers on generated cards
+struct Random(u64);
+
+impl Random {
+    fn below(&mut self, limit: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % limit
+    }
+}
+
+/// Generates a table shaped like the puzzle input: 10 winning and 25 actual numbers below 100
+fn generate_input(card_count: usize) -> String {
+    let mut random = Random(0x2545_f491_4f6c_dd1d);
+    let mut input = String::new();
+
+    for card_id in 1..=card_count {
+        let winning: Vec<String> = (0..10).map(|_| random.below(100).to_string()).collect();
+        let actual: Vec<String> = (0..25).map(|_| random.below(100).to_string()).collect();
+
+        writeln!(
+            input,
+            "Card {}: {} | {}",
+            card_id,
+            winning.join(" "),
+            actual.join(" ")
+        )
+        .unwrap();
+    }
+
+    input
+}
+
+fn print_throughput(name: &str, card_count: usize, run: impl Fn() -> u64) -> f64 {
+    let start = Instant::now();
+    let mut total = 0;
+
+    for _ in 0..ROUNDS {
+        total += black_box(run());
+    }
+
+    let elapsed = start.elapsed().as_secs_f64();
+    let cards_per_second = (card_count * ROUNDS) as f64 / elapsed;
+
+    println!(
+        "{}: {:.3}s, {:.0} cards/s (checksum {})",
+        name, elapsed, cards_per_second, total
+    );
+
+    cards_per_second
+}
+
+pub fn run(card_count: usize) {
+    let input = generate_input(card_count);
+    let cards: Vec<Card> = parse_cards(&input).expect("Generated cards should have distinct ids");
+
+    let sets: Vec<(HashSet<u32>, HashSet<u32>)> = cards
+        .iter()
+        .map(|card| (card.winning_numbers.to_set(), card.actual_numbers.to_set()))
+        .collect();
+
+    println!("matching {} cards {} times", card_count, ROUNDS);
+
+    let set_throughput = print_throughput("hash sets", card_count, || {
+        sets.iter()
+            .map(|(winning, actual)| winning.intersection(actual).count() as u64)
+            .sum()
+    });
+
+    let bitset_throughput = print_throughput("bitsets", card_count, || {
+        cards
+            .iter()
+            .map(|card| card.matching_numbers() as u64)
+            .sum()
+    });
+
+    println!("speedup: x{:.1}", bitset_throughput / set_throughput);
+}
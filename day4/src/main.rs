@@ -1,41 +1,48 @@
-use std::collections::HashSet;
+use std::{collections::HashMap, fmt};
 
-use regex::Regex;
+use num_bigint::BigUint;
+use num_traits::Zero;
+use numbers::Numbers;
+use rules::{CopyRule, Doubling, Fibonacci, Linear, NextCards, PreviousCards, Scoring};
+
+mod bench;
+mod numbers;
+mod rules;
 
 #[derive(Debug)]
 struct Card {
-    winning_numbers: HashSet<u32>,
-    actual_numbers: HashSet<u32>,
+    id: u32,
+    winning_numbers: Numbers,
+    actual_numbers: Numbers,
 }
 
-fn parse_number(number_list: &str) -> HashSet<u32> {
-    let number_parsing_re = Regex::new(r"(\d+)").unwrap();
-
-    number_parsing_re
-        .captures_iter(number_list)
-        .map(|capture| capture.get(0).unwrap())
-        .map(|number| {
-            number
-                .as_str()
-                .parse::<u32>()
-                .expect("Number should be a number")
-        })
-        .collect::<HashSet<u32>>()
+fn parse_number(number_list: &str) -> Numbers {
+    number_list
+        .split_whitespace()
+        .map(|number| number.parse::<u32>().expect("Number should be a number"))
+        .collect()
 }
 
 impl From<&str> for Card {
     fn from(line: &str) -> Self {
-        let card_info = line.split(":").collect::<Vec<&str>>();
+        let card_info = line.split(':').collect::<Vec<&str>>();
+        let id = card_info
+            .first()
+            .and_then(|introduction| introduction.split_whitespace().next_back())
+            .expect("Card should have an ID before :")
+            .parse::<u32>()
+            .expect("Card ID should be a number");
         let card_numbers = *card_info.get(1).expect("Card should have numbers");
 
-        let numbers = card_numbers.split("|").collect::<Vec<&str>>();
-        let winning_numbers = *numbers.get(0).expect("Card should have winning numbers");
+        let numbers = card_numbers.split('|').collect::<Vec<&str>>();
+        let winning_numbers = *numbers.first().expect("Card should have winning numbers");
         let winning_numbers = parse_number(winning_numbers);
 
         let actual_numbers = *numbers.get(1).expect("Card should have actual numbers");
         let actual_numbers = parse_number(actual_numbers);
 
         Card {
+            id,
             winning_numbers,
             actual_numbers,
         }
@@ -44,52 +51,272 @@ impl From<&str> for Card {
 
 impl Card {
     fn matching_numbers(&self) -> u32 {
-        self.winning_numbers
-            .intersection(&self.actual_numbers)
-            .count() as u32
+        self.winning_numbers.intersection_size(&self.actual_numbers)
     }
+}
 
-    fn points(&self) -> u32 {
-        let intersection_size = self.matching_numbers();
+/// What to do when a card wins copies of cards missing from the table
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OverflowPolicy {
+    // only win copies of the cards that exist
+    Clamp,
+    // refuse the table
+    Error,
+}
+
+#[derive(Debug)]
+enum CascadeError {
+    MissingCards {
+        card_id: u32,
+        won: usize,
+        available: usize,
+    },
+    // a card wins copies of itself, directly or not, so the cascade never ends
+    Cycle {
+        card_id: u32,
+    },
+}
 
-        if intersection_size == 0 {
-            0
-        } else {
-            2u32.pow(intersection_size - 1)
+impl fmt::Display for CascadeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CascadeError::MissingCards {
+                card_id,
+                won,
+                available,
+            } => write!(
+                f,
+                "card {} wins copies of {} cards but only {} of them exist",
+                card_id, won, available
+            ),
+            CascadeError::Cycle { card_id } => {
+                write!(f, "card {} ends up winning copies of itself", card_id)
+            }
         }
     }
 }
 
-fn ex1(input: &str) -> u32 {
-    input.lines().map(|line| Card::from(line).points()).sum()
+/// Two cards of the table have the same id
+#[derive(Debug)]
+struct DuplicateCardError {
+    card_id: u32,
+}
+
+impl fmt::Display for DuplicateCardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "card {} appears more than once", self.card_id)
+    }
+}
+
+fn parse_cards(input: &str) -> Result<Vec<Card>, DuplicateCardError> {
+    let mut cards = input.lines().map(Card::from).collect::<Vec<Card>>();
+
+    // ids may come unsorted or with gaps, copies are won by id
+    cards.sort_by_key(|card| card.id);
+
+    match cards.windows(2).find(|pair| pair[0].id == pair[1].id) {
+        Some(pair) => Err(DuplicateCardError {
+            card_id: pair[0].id,
+        }),
+        None => Ok(cards),
+    }
+}
+
+fn ex1(cards: &[Card], scoring: &dyn Scoring) -> BigUint {
+    cards
+        .iter()
+        .map(|card| scoring.points(card.matching_numbers()))
+        .sum()
+}
+
+/// How a card took part in the copy cascade
+#[derive(Debug)]
+struct CascadeStep {
+    card_id: u32,
+    // instances of the card, the original included
+    copies: BigUint,
+    // copies won from other cards
+    received: BigUint,
+    // copies handed to other cards
+    produced: BigUint,
 }
 
-fn ex2(input: &str) -> u32 {
-    let mut total_scratch_cards = 0;
+fn cascade(
+    cards: &[Card],
+    copy_rule: &dyn CopyRule,
+    policy: OverflowPolicy,
+) -> Result<Vec<CascadeStep>, CascadeError> {
+    let index_by_id: HashMap<i64, usize> = cards
+        .iter()
+        .enumerate()
+        .map(|(card_index, card)| (card.id as i64, card_index))
+        .collect();
+
+    let mut won_cards: Vec<Vec<usize>> = Vec::with_capacity(cards.len());
+
+    for card in cards.iter() {
+        let won = copy_rule.won_cards(card.id, card.matching_numbers());
+        let won_count = won.clone().count();
+        let existing: Vec<usize> = won
+            .filter_map(|won_id| index_by_id.get(&won_id).copied())
+            .collect();
+
+        if existing.len() < won_count && policy == OverflowPolicy::Error {
+            return Err(CascadeError::MissingCards {
+                card_id: card.id,
+                won: won_count,
+                available: existing.len(),
+            });
+        }
+
+        won_cards.push(existing);
+    }
+
+    // a card can only hand its copies once every card winning it has been processed
+    let mut pending_givers = vec![0; cards.len()];
+    for won in won_cards.iter() {
+        for won_index in won.iter() {
+            pending_givers[*won_index] += 1;
+        }
+    }
+
+    let mut ready: Vec<usize> = (0..cards.len())
+        .rev()
+        .filter(|card_index| pending_givers[*card_index] == 0)
+        .collect();
 
-    let cards = input
-        .lines()
-        .map(|line| Card::from(line))
-        .collect::<Vec<Card>>();
+    // copies can grow exponentially along the table, overflowing any fixed size integer
+    let mut received = vec![BigUint::zero(); cards.len()];
+    let mut copies = vec![BigUint::zero(); cards.len()];
+    let mut processed = 0;
 
-    let mut multipliers = vec![1; cards.len()];
+    while let Some(card_index) = ready.pop() {
+        copies[card_index] = &received[card_index] + 1u32;
+        processed += 1;
 
-    for (card_id, card) in cards.iter().enumerate() {
-        let current_card_multiplier = multipliers[card_id];
-        total_scratch_cards += current_card_multiplier;
+        for won_index in won_cards[card_index].iter() {
+            received[*won_index] += &copies[card_index];
 
-        let bonus_card_range = card.matching_numbers() as usize;
-        for affected_card_index in card_id + 1..=(card_id + bonus_card_range) {
-            multipliers[affected_card_index] += current_card_multiplier
+            pending_givers[*won_index] -= 1;
+            if pending_givers[*won_index] == 0 {
+                ready.push(*won_index);
+            }
         }
     }
 
-    total_scratch_cards
+    if processed < cards.len() {
+        let stuck_index = pending_givers
+            .iter()
+            .position(|givers| *givers > 0)
+            .expect("A card should be waiting for its givers");
+
+        return Err(CascadeError::Cycle {
+            card_id: cards[stuck_index].id,
+        });
+    }
+
+    Ok(cards
+        .iter()
+        .zip(copies)
+        .zip(received)
+        .zip(won_cards)
+        .map(|(((card, copies), received), won)| CascadeStep {
+            card_id: card.id,
+            produced: &copies * won.len(),
+            received,
+            copies,
+        })
+        .collect())
+}
+
+fn ex2(
+    cards: &[Card],
+    copy_rule: &dyn CopyRule,
+    policy: OverflowPolicy,
+) -> Result<BigUint, CascadeError> {
+    let steps = cascade(cards, copy_rule, policy)?;
+
+    Ok(steps.iter().map(|step| &step.copies).sum())
+}
+
+fn print_trace(
+    cards: &[Card],
+    copy_rule: &dyn CopyRule,
+    policy: OverflowPolicy,
+) -> Result<(), CascadeError> {
+    for step in cascade(cards, copy_rule, policy)? {
+        println!(
+            "card {}: {} copies, received {}, produced {}",
+            step.card_id, step.copies, step.received, step.produced
+        );
+    }
+
+    Ok(())
+}
+
+fn parse_scoring(name: &str) -> Box<dyn Scoring> {
+    match name {
+        "doubling" => Box::new(Doubling),
+        "linear" => Box::new(Linear),
+        "fibonacci" => Box::new(Fibonacci),
+        _ => panic!("Unknown scoring '{}'", name),
+    }
+}
+
+fn parse_copy_rule(name: &str) -> Box<dyn CopyRule> {
+    match name {
+        "next" => Box::new(NextCards),
+        "previous" => Box::new(PreviousCards),
+        _ => panic!("Unknown copy rule '{}'", name),
+    }
 }
 
 fn main() {
     let input = include_str!("../etc/input");
+    let cards = parse_cards(input).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        std::process::exit(1);
+    });
 
-    println!("{}", ex1(input));
-    println!("{}", ex2(input));
+    let mut scoring = parse_scoring("doubling");
+    let mut copy_rule = parse_copy_rule("next");
+    let mut policy = OverflowPolicy::Clamp;
+    let mut trace = false;
+
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--strict" => policy = OverflowPolicy::Error,
+            "--trace" => trace = true,
+            "--bench" => {
+                let card_count = args
+                    .next()
+                    .map(|count| count.parse().expect("Card count should be a number"))
+                    .unwrap_or(300_000);
+                bench::run(card_count);
+                return;
+            }
+            "--scoring" => {
+                scoring = parse_scoring(&args.next().expect("--scoring should have a value"))
+            }
+            "--copies" => {
+                copy_rule = parse_copy_rule(&args.next().expect("--copies should have a value"))
+            }
+            _ => panic!("Unknown argument '{}'", arg),
+        }
+    }
+
+    let result = if trace {
+        print_trace(&cards, copy_rule.as_ref(), policy)
+    } else {
+        println!("{}", ex1(&cards, scoring.as_ref()));
+        ex2(&cards, copy_rule.as_ref(), policy)
+            .map(|total_scratch_cards| println!("{}", total_scratch_cards))
+    };
+
+    if let Err(cascade_error) = result {
+        eprintln!("{}", cascade_error);
+        std::process::exit(1);
+    }
 }
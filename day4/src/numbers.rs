@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+
+// numbers below this limit fit in a bitset
+const BITSET_CAPACITY: u32 = u128::BITS;
+
+/// A set of card numbers, stored as a bitset when they are all small enough
+#[derive(Debug, Clone, PartialEq)]
+pub enum Numbers {
+    Bits(u128),
+    Set(HashSet<u32>),
+}
+
+impl FromIterator<u32> for Numbers {
+    fn from_iter<I: IntoIterator<Item = u32>>(numbers: I) -> Self {
+        let mut bits = 0u128;
+        let mut numbers = numbers.into_iter();
+
+        while let Some(number) = numbers.next() {
+            if number >= BITSET_CAPACITY {
+                // falling back to a hash set with the numbers read so far
+                let mut set = Numbers::Bits(bits).to_set();
+                set.insert(number);
+                set.extend(numbers);
+
+                return Numbers::Set(set);
+            }
+
+            bits |= 1 << number;
+        }
+
+        Numbers::Bits(bits)
+    }
+}
+
+impl Numbers {
+    pub fn contains(&self, number: u32) -> bool {
+        match self {
+            Numbers::Bits(bits) => number < BITSET_CAPACITY && bits & (1 << number) != 0,
+            Numbers::Set(set) => set.contains(&number),
+        }
+    }
+
+    pub fn to_set(&self) -> HashSet<u32> {
+        match self {
+            Numbers::Bits(bits) => (0..BITSET_CAPACITY)
+                .filter(|number| bits & (1 << number) != 0)
+                .collect(),
+            Numbers::Set(set) => set.clone(),
+        }
+    }
+
+    /// Counts the numbers present in both sets
+    pub fn intersection_size(&self, other: &Numbers) -> u32 {
+        match (self, other) {
+            (Numbers::Bits(bits), Numbers::Bits(other_bits)) => (bits & other_bits).count_ones(),
+            (Numbers::Set(set), Numbers::Set(other_set)) => {
+                set.intersection(other_set).count() as u32
+            }
+            (Numbers::Set(set), bits) | (bits, Numbers::Set(set)) => {
+                set.iter().filter(|number| bits.contains(**number)).count() as u32
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::ops::Range;
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+/// How many points a card is worth given its matching numbers
+pub trait Scoring {
+    // points grow past any fixed size integer with enough matches
+    fn points(&self, matching_numbers: u32) -> BigUint;
+}
+
+/// 1 point for the first match, then doubled for each other match
+pub struct Doubling;
+
+impl Scoring for Doubling {
+    fn points(&self, matching_numbers: u32) -> BigUint {
+        if matching_numbers == 0 {
+            BigUint::zero()
+        } else {
+            BigUint::one() << (matching_numbers - 1)
+        }
+    }
+}
+
+/// 1 point per match
+pub struct Linear;
+
+impl Scoring for Linear {
+    fn points(&self, matching_numbers: u32) -> BigUint {
+        BigUint::from(matching_numbers)
+    }
+}
+
+/// Points following the Fibonacci sequence: 0, 1, 1, 2, 3, 5...
+pub struct Fibonacci;
+
+impl Scoring for Fibonacci {
+    fn points(&self, matching_numbers: u32) -> BigUint {
+        let (points, _) =
+            (0..matching_numbers).fold((BigUint::zero(), BigUint::one()), |(current, next), _| {
+                let following = &current + &next;
+                (next, following)
+            });
+
+        points
+    }
+}
+
+/// Which cards a card wins copies of given its matching numbers
+pub trait CopyRule {
+    /// Ids of the won cards, possibly missing from the table
+    fn won_cards(&self, card_id: u32, matching_numbers: u32) -> Range<i64>;
+}
+
+/// Copies of the n cards following the card
+pub struct NextCards;
+
+impl CopyRule for NextCards {
+    fn won_cards(&self, card_id: u32, matching_numbers: u32) -> Range<i64> {
+        let card_id = card_id as i64;
+
+        card_id + 1..card_id + 1 + matching_numbers as i64
+    }
+}
+
+/// Copies of the n cards preceding the card
+pub struct PreviousCards;
+
+impl CopyRule for PreviousCards {
+    fn won_cards(&self, card_id: u32, matching_numbers: u32) -> Range<i64> {
+        let card_id = card_id as i64;
+
+        card_id - matching_numbers as i64..card_id
+    }
+}
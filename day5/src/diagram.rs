@@ -0,0 +1,194 @@
+use std::{fmt::Write, ops::RangeInclusive};
+
+use intervals::IntervalSet;
+
+use crate::{piecewise::PiecewiseMap, Operation};
+
+const STYLE: &str = ".column { fill: #ddd; } \
+.band { fill: #999; fill-opacity: 0.25; stroke: #777; stroke-width: 0.5; } \
+.flow { fill-opacity: 0.6; } \
+.label { font-family: sans-serif; font-size: 12px; text-anchor: middle; }";
+
+// layout of the diagram, in pixels
+const MARGIN: f64 = 40.0;
+const HEIGHT: f64 = 600.0;
+const COLUMN_WIDTH: f64 = 12.0;
+const COLUMN_GAP: f64 = 180.0;
+
+// a range flowing from one column to the next one
+struct Flow {
+    stage: usize,
+    source: RangeInclusive<u64>,
+    destination: RangeInclusive<u64>,
+}
+
+/// Draws each category as a column, with bands for the mappers between them and the seed ranges
+/// flowing through them
+pub struct Diagram<'r, 'a> {
+    operations: &'r [&'r Operation<'a>],
+    // seed ranges as listed, each drawn in its own color
+    seed_ranges: Vec<RangeInclusive<u64>>,
+    // highest number drawn, at the bottom of the columns
+    max: u64,
+}
+
+impl<'r, 'a> Diagram<'r, 'a> {
+    pub fn new(
+        operations: &'r [&'r Operation<'a>],
+        seed_ranges: Vec<RangeInclusive<u64>>,
+    ) -> Diagram<'r, 'a> {
+        let mapper_ends = operations
+            .iter()
+            .flat_map(|operation| operation.mappers.iter())
+            .flat_map(|mapper| [mapper.src_end, mapper.map(mapper.src_end)]);
+        let seed_ends = seed_ranges.iter().map(|range| *range.end());
+
+        let max = mapper_ends.chain(seed_ends).max().unwrap_or(0);
+
+        Diagram {
+            operations,
+            seed_ranges,
+            max,
+        }
+    }
+
+    fn x(&self, column: usize) -> f64 {
+        MARGIN + column as f64 * COLUMN_GAP
+    }
+
+    // top and bottom of a range on a column
+    fn y(&self, range: &RangeInclusive<u64>) -> (f64, f64) {
+        let scale = HEIGHT / (self.max as f64 + 1.0);
+        let top = MARGIN + *range.start() as f64 * scale;
+        let bottom = MARGIN + (*range.end() as f64 + 1.0) * scale;
+
+        // keeping thin ranges visible
+        (top, bottom.max(top + 1.0))
+    }
+
+    // the seed ranges split and shifted at each stage, for one listed seed range
+    fn flows(&self, seed_range: &RangeInclusive<u64>) -> Vec<Flow> {
+        let mut flows = Vec::new();
+        let mut current = IntervalSet::from(seed_range.clone());
+
+        for (stage, operation) in self.operations.iter().enumerate() {
+            let map = PiecewiseMap::from(*operation);
+            let mut next = IntervalSet::new();
+
+            for range in current.iter() {
+                let mut rest = Some(range.clone());
+
+                while let Some(range) = rest {
+                    let (image, remaining) = map.split_first(&range);
+                    let source_end = range.start() + (image.end() - image.start());
+
+                    flows.push(Flow {
+                        stage,
+                        source: *range.start()..=source_end,
+                        destination: image.clone(),
+                    });
+                    next.insert(image);
+                    rest = remaining;
+                }
+            }
+
+            current = next;
+        }
+
+        flows
+    }
+
+    // a band linking a range on a column to a range on the next one
+    fn band(&self, output: &mut String, flow: &Flow, attributes: &str) {
+        let (left, right) = (self.x(flow.stage) + COLUMN_WIDTH, self.x(flow.stage + 1));
+        let (source_top, source_bottom) = self.y(&flow.source);
+        let (destination_top, destination_bottom) = self.y(&flow.destination);
+
+        writeln!(
+            output,
+            r#"<polygon points="{:.1},{:.1} {:.1},{:.1} {:.1},{:.1} {:.1},{:.1}" {}><title>{:?} -> {:?}</title></polygon>"#,
+            left,
+            source_top,
+            right,
+            destination_top,
+            right,
+            destination_bottom,
+            left,
+            source_bottom,
+            attributes,
+            flow.source,
+            flow.destination
+        )
+        .unwrap();
+    }
+
+    pub fn svg(&self) -> String {
+        let columns = self.operations.len() + 1;
+        let width = 2.0 * MARGIN + (columns - 1) as f64 * COLUMN_GAP + COLUMN_WIDTH;
+        let height = 2.0 * MARGIN + HEIGHT;
+
+        let mut output = String::new();
+
+        writeln!(
+            output,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}">"#,
+            width, height
+        )
+        .unwrap();
+        writeln!(output, "<style>{}</style>", STYLE).unwrap();
+
+        let categories = self
+            .operations
+            .first()
+            .map(|operation| operation.source)
+            .into_iter()
+            .chain(
+                self.operations
+                    .iter()
+                    .map(|operation| operation.destination),
+            );
+
+        for (column, category) in categories.enumerate() {
+            let x = self.x(column);
+
+            writeln!(
+                output,
+                r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" class="column"/>"#,
+                x, MARGIN, COLUMN_WIDTH, HEIGHT
+            )
+            .unwrap();
+            writeln!(
+                output,
+                r#"<text x="{:.1}" y="{:.1}" class="label">{}</text>"#,
+                x + COLUMN_WIDTH / 2.0,
+                MARGIN / 2.0,
+                category
+            )
+            .unwrap();
+        }
+
+        for (stage, operation) in self.operations.iter().enumerate() {
+            for mapper in operation.mappers.iter() {
+                let flow = Flow {
+                    stage,
+                    source: mapper.src_range(),
+                    destination: mapper.map(mapper.src_start)..=mapper.map(mapper.src_end),
+                };
+
+                self.band(&mut output, &flow, r#"class="band""#);
+            }
+        }
+
+        for (index, seed_range) in self.seed_ranges.iter().enumerate() {
+            let hue = index * 360 / self.seed_ranges.len();
+            let color = format!(r#"class="flow" fill="hsl({}, 70%, 45%)""#, hue);
+
+            for flow in self.flows(seed_range) {
+                self.band(&mut output, &flow, &color);
+            }
+        }
+
+        output.push_str("</svg>\n");
+        output
+    }
+}
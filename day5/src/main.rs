@@ -1,4 +1,17 @@
-use std::ops::RangeInclusive;
+use std::{fmt, ops::RangeInclusive};
+
+use diagram::Diagram;
+use intervals::IntervalSet;
+use piecewise::PiecewiseMap;
+use routes::{Route, RouteError};
+use search::LocationSearch;
+use validation::MapReport;
+
+mod diagram;
+mod piecewise;
+mod routes;
+mod search;
+mod validation;
 
 #[derive(Debug)]
 struct Mapper {
@@ -7,9 +20,11 @@ struct Mapper {
     dest_start: u64,
 }
 
-impl From<&str> for Mapper {
-    fn from(line: &str) -> Self {
-        let mut line_it = line.split(" ");
+impl TryFrom<&str> for Mapper {
+    type Error = MapperError;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        let mut line_it = line.split(' ');
 
         let dest_start = line_it
             .next()
@@ -28,149 +43,176 @@ impl From<&str> for Mapper {
             .parse::<u64>()
             .expect("Expect range width to be a number");
 
-        Mapper {
+        let last_step = range_width
+            .checked_sub(1)
+            .ok_or(MapperError::ZeroWidth { src_start })?;
+
+        let src_end = src_start
+            .checked_add(last_step)
+            .ok_or(MapperError::SourceOverflow {
+                src_start,
+                range_width,
+            })?;
+
+        if dest_start.checked_add(last_step).is_none() {
+            return Err(MapperError::DestinationOverflow {
+                dest_start,
+                range_width,
+            });
+        }
+
+        Ok(Mapper {
             src_start,
-            src_end: src_start + range_width - 1,
+            src_end,
             dest_start,
-        }
+        })
     }
 }
 
 impl Mapper {
+    fn offset(&self) -> i128 {
+        self.dest_start as i128 - self.src_start as i128
+    }
+
+    fn src_range(&self) -> RangeInclusive<u64> {
+        self.src_start..=self.src_end
+    }
+
     fn map(&self, source: u64) -> u64 {
         source - self.src_start + self.dest_start
     }
 
-    fn is_covering(&self, number: u64) -> bool {
-        (self.src_start..=self.src_end).contains(&number)
+    fn unmap(&self, destination: u64) -> u64 {
+        destination - self.dest_start + self.src_start
     }
+}
 
-    fn is_overlapping(&self, number_range: &RangeInclusive<u64>) -> bool {
-        let src_range = &self.src_start..&self.src_end;
+/// Why a line of a map cannot be turned into a mapper
+#[derive(Debug, PartialEq)]
+enum MapperError {
+    ZeroWidth { src_start: u64 },
+    SourceOverflow { src_start: u64, range_width: u64 },
+    DestinationOverflow { dest_start: u64, range_width: u64 },
+}
 
-        number_range.contains(&self.src_start)
-            || number_range.contains(&self.src_end)
-            || src_range.contains(&number_range.start())
-            || src_range.contains(&number_range.end())
+impl fmt::Display for MapperError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapperError::ZeroWidth { src_start } => {
+                write!(f, "mapper at source {} has a width of 0", src_start)
+            }
+            MapperError::SourceOverflow {
+                src_start,
+                range_width,
+            } => write!(
+                f,
+                "source range of width {} starting at {} goes past {}",
+                range_width,
+                src_start,
+                u64::MAX
+            ),
+            MapperError::DestinationOverflow {
+                dest_start,
+                range_width,
+            } => write!(
+                f,
+                "destination range of width {} starting at {} goes past {}",
+                range_width,
+                dest_start,
+                u64::MAX
+            ),
+        }
     }
 }
 
 #[derive(Debug)]
-struct Operation {
+struct Operation<'a> {
+    source: &'a str,
+    destination: &'a str,
     mappers: Vec<Mapper>,
+    // lines skipped as they do not describe a usable mapper
+    rejected: Vec<MapperError>,
 }
 
-impl From<&str> for Operation {
-    fn from(operation_description: &str) -> Self {
-        let lines = operation_description.split("\n");
-
-        // drop first line as operation name are not important
-        let mut mappers = lines
-            .skip(1)
-            .map(|mapper_description| Mapper::from(mapper_description))
-            .collect::<Vec<Mapper>>();
-
-        mappers.sort_by_key(|mapper| mapper.src_start);
-
-        Operation { mappers }
-    }
-}
+impl<'a> From<&'a str> for Operation<'a> {
+    fn from(operation_description: &'a str) -> Self {
+        let mut lines = operation_description.split('\n');
 
-impl Operation {
-    fn map(&self, number: u64) -> u64 {
-        self.closest_mapper(number)
-            .map(|mapper| mapper.map(number))
-            .unwrap_or(number)
-    }
+        // first line names the categories, as in "seed-to-soil map:"
+        let (source, destination) = lines
+            .next()
+            .and_then(|header| header.strip_suffix(" map:"))
+            .and_then(|categories| categories.split_once("-to-"))
+            .expect("Expecting a map header like 'source-to-destination map:'");
 
-    fn closest_mapper(&self, number: u64) -> Option<&Mapper> {
-        let closest_mapper_id = self
-            .mappers
-            .binary_search_by_key(&number, |mapper| mapper.src_start);
+        let mut mappers = Vec::new();
+        let mut rejected = Vec::new();
 
-        if let Ok(mapper_id) = closest_mapper_id {
-            return self.mappers.get(mapper_id);
+        for line in lines {
+            match Mapper::try_from(line) {
+                Ok(mapper) => mappers.push(mapper),
+                Err(error) => rejected.push(error),
+            }
         }
 
-        let previous_id = closest_mapper_id.err().unwrap();
+        mappers.sort_by_key(|mapper| mapper.src_start);
 
-        if previous_id == 0 {
-            return None;
+        Operation {
+            source,
+            destination,
+            mappers,
+            rejected,
         }
-
-        return self
-            .mappers
-            .get(previous_id - 1)
-            .filter(|previous_mapper| previous_mapper.is_covering(number));
     }
+}
 
-    fn map_range(&self, number_range: &RangeInclusive<u64>) -> Vec<RangeInclusive<u64>> {
-        let matching_mappers = self.matching_mappers(number_range);
-
-        let mut mapped_ranges: Vec<RangeInclusive<u64>> = Vec::new();
-
-        // set end of previous mapper as start to fill gap if necessary with a 1:1 range
-        let mut previous_mapper_end = *number_range.start();
-
-        for matching_mapper in matching_mappers.iter() {
-            // if previous mapper finished before remaining range start
-            if previous_mapper_end < matching_mapper.src_start {
-                // add a 1:1 mapping in between matching mappers
-                mapped_ranges.push(RangeInclusive::new(
-                    previous_mapper_end,
-                    matching_mapper.src_start - 1,
-                ));
-                // mimick as if the previous mapper finished at the next mapper start
-                previous_mapper_end = matching_mapper.src_start;
-            }
-
-            let mapped_start = matching_mapper.map(previous_mapper_end);
-
-            let mapped_end = if *number_range.end() > matching_mapper.src_end {
-                previous_mapper_end = matching_mapper.src_end + 1;
-                matching_mapper.map(matching_mapper.src_end)
-            } else {
-                previous_mapper_end = *number_range.end() + 1;
-                matching_mapper.map(*number_range.end())
-            };
+impl Operation<'_> {
+    fn mapped_sources(&self) -> IntervalSet {
+        self.mappers.iter().map(Mapper::src_range).collect()
+    }
 
-            mapped_ranges.push(RangeInclusive::new(mapped_start, mapped_end));
-        }
+    /// Returns every number mapped into the targets
+    fn inverse_ranges(&self, targets: &IntervalSet) -> IntervalSet {
+        let mut sources = IntervalSet::new();
+        // when mappers overlap, the one starting first applies
+        let mut claimed = IntervalSet::new();
 
-        // if the last mapper finished before the end, fill gap with 1:1 range
-        if previous_mapper_end <= *number_range.end() {
-            mapped_ranges.push(RangeInclusive::new(
-                previous_mapper_end,
-                *number_range.end(),
-            ))
+        for mapper in self.mappers.iter() {
+            let owned = IntervalSet::from(mapper.src_range()).difference(&claimed);
+            let owned_destinations: IntervalSet = owned
+                .iter()
+                .map(|range| mapper.map(*range.start())..=mapper.map(*range.end()))
+                .collect();
+
+            sources.extend(
+                targets
+                    .intersection(&owned_destinations)
+                    .iter()
+                    .map(|range| mapper.unmap(*range.start())..=mapper.unmap(*range.end())),
+            );
+            claimed.insert(mapper.src_range());
         }
 
-        mapped_ranges
-    }
-
-    fn matching_mappers(&self, range: &RangeInclusive<u64>) -> Vec<&Mapper> {
-        self.mappers
-            .iter()
-            .filter(|mapper| mapper.is_overlapping(&range))
-            .collect()
+        // numbers outside of every mapper map to themselves
+        sources.union(&targets.difference(&self.mapped_sources()))
     }
 }
 
 #[derive(Debug)]
-struct Almanac {
+struct Almanac<'a> {
     seeds: Vec<u64>,
-    operations: Vec<Operation>,
+    operations: Vec<Operation<'a>>,
 }
 
-impl From<&str> for Almanac {
-    fn from(input: &str) -> Self {
+impl<'a> From<&'a str> for Almanac<'a> {
+    fn from(input: &'a str) -> Self {
         // reading seeds
         let (seeds, input) = input
-            .split_once("\n")
+            .split_once('\n')
             .expect("Expecting multiple lines in file");
         let (_, seeds) = seeds.split_once(": ").expect("Expecting seeds after :");
         let seeds = seeds
-            .split(" ")
+            .split(' ')
             .map(|seed_number| {
                 seed_number
                     .parse::<u64>()
@@ -179,75 +221,233 @@ impl From<&str> for Almanac {
             .collect();
 
         // reading operations
-        let operations = input[1..]
-            .split("\n\n")
-            .map(|operation_description| Operation::from(operation_description))
-            .collect();
+        let operations = input[1..].split("\n\n").map(Operation::from).collect();
 
         Almanac { seeds, operations }
     }
 }
 
-impl Almanac {
-    fn map(&self, seed_number: u64) -> u64 {
-        self.operations
-            .iter()
-            .fold(seed_number, |number_to_map, operation| {
-                operation.map(number_to_map)
-            })
+impl<'a> Almanac<'a> {
+    // seed ranges in listing order, possibly overlapping
+    fn listed_seed_ranges(&self) -> impl Iterator<Item = RangeInclusive<u64>> + '_ {
+        self.seeds.chunks(2).map(|pair| {
+            let start = pair[0];
+            let end = pair[0] + pair[1] - 1;
+            RangeInclusive::new(start, end)
+        })
     }
 
-    fn seed_ranges(&self) -> Vec<RangeInclusive<u64>> {
-        self.seeds
-            .chunks(2)
-            .map(|pair| {
-                let start = pair[0];
-                let end = pair[0] + pair[1] - 1;
-                RangeInclusive::new(start, end)
-            })
-            .collect()
+    fn seed_ranges(&self) -> IntervalSet {
+        self.listed_seed_ranges().collect()
     }
 
-    fn map_range(&self, seed_range: &RangeInclusive<u64>) -> Vec<RangeInclusive<u64>> {
-        let mut ranges = vec![seed_range.clone()];
+    /// Returns the seed at a position, counting from 1 across seed ranges in listing order
+    fn nth_seed(&self, position: u64) -> Option<u64> {
+        let mut skipped = position.checked_sub(1)?;
 
-        for operation in self.operations.iter() {
-            ranges = ranges
-                .iter()
-                .map(|range| operation.map_range(range))
-                .flatten()
-                .collect::<Vec<RangeInclusive<u64>>>();
+        for pair in self.seeds.chunks(2) {
+            if skipped < pair[1] {
+                return Some(pair[0] + skipped);
+            }
+
+            skipped -= pair[1];
         }
 
-        ranges
+        None
+    }
+
+    /// Returns the maps leading from one category to another, by name
+    fn route(&self, from: &str, to: &str) -> Result<Route<'_, 'a>, RouteError> {
+        routes::find(&self.operations, from, to)
+    }
+
+    fn validate(&self) -> Vec<MapReport<'_, 'a>> {
+        self.operations.iter().map(validation::validate).collect()
     }
 }
 
-fn ex1(almanac: &Almanac) -> u64 {
+fn ex1(almanac: &Almanac, composed: &PiecewiseMap) -> u64 {
     almanac
         .seeds
         .iter()
-        .map(|seed_number| almanac.map(*seed_number))
+        .map(|seed_number| composed.map(*seed_number))
         .min()
         .expect("Expect at least one seed location")
 }
 
-fn ex2(almanac: &Almanac) -> u64 {
-    almanac
-        .seed_ranges()
-        .iter()
-        .map(|seed_number| almanac.map_range(seed_number))
-        .flatten()
-        .map(|range| *range.start())
-        .min()
+fn ex2(almanac: &Almanac, composed: &PiecewiseMap) -> u64 {
+    LocationSearch::new(composed, &almanac.seed_ranges())
+        .next()
         .expect("Expect at least one seed location")
 }
 
+fn parse_number(number: &str) -> u64 {
+    number.parse::<u64>().expect("Expecting a number to map")
+}
+
+// the numbers given on the command line, a single one or a range
+fn parse_numbers(args: &[String]) -> IntervalSet {
+    match args {
+        [number] => IntervalSet::from(parse_number(number)..=parse_number(number)),
+        [start, end] => IntervalSet::from(parse_number(start)..=parse_number(end)),
+        _ => panic!("Expecting a number or a start and an end number"),
+    }
+}
+
+fn warn_rejected(almanac: &Almanac) {
+    for operation in almanac.operations.iter() {
+        for error in operation.rejected.iter() {
+            eprintln!(
+                "Ignoring a line of the {}-to-{} map: {}",
+                operation.source, operation.destination, error
+            );
+        }
+    }
+}
+
+fn route_or_exit<'r, 'a>(almanac: &'r Almanac<'a>, from: &str, to: &str) -> Route<'r, 'a> {
+    almanac.route(from, to).unwrap_or_else(|error| {
+        eprintln!("Cannot map {} to {}: {}", from, to, error);
+        std::process::exit(1);
+    })
+}
+
+// prints the numbers of a category reached from numbers of another one
+fn print_route(almanac: &Almanac, args: &[String]) {
+    let [from, to, numbers @ ..] = args else {
+        panic!("Expecting a source category, a destination category and numbers");
+    };
+
+    let route = route_or_exit(almanac, from, to);
+
+    println!("route: {}", route.categories().join(" -> "));
+    println!("{}: {}", to, route.map_ranges(&parse_numbers(numbers)));
+}
+
+// the function taking seeds to their locations, for the commands working on the whole almanac
+fn compose_or_exit(almanac: &Almanac) -> PiecewiseMap {
+    route_or_exit(almanac, "seed", "location")
+        .compose()
+        .expect("Seeds should lead forward to locations")
+}
+
+// prints the seeds ending up in a location, or in a range of locations
+fn print_inverse(almanac: &Almanac, args: &[String]) {
+    let seeds = route_or_exit(almanac, "location", "seed").map_ranges(&parse_numbers(args));
+
+    let listed_seeds: Vec<u64> = almanac
+        .seeds
+        .iter()
+        .copied()
+        .filter(|seed| seeds.contains(*seed))
+        .collect();
+
+    println!("seeds: {}", seeds);
+    println!("listed seeds: {:?}", listed_seeds);
+    println!(
+        "seeds in seed ranges: {}",
+        seeds.intersection(&almanac.seed_ranges())
+    );
+}
+
+// prints the k smallest locations reached from seed ranges
+fn print_smallest(almanac: &Almanac, composed: &PiecewiseMap, args: &[String]) {
+    let count = match args {
+        [count] => count.parse::<usize>().expect("Count should be a number"),
+        _ => panic!("Expecting how many locations to list"),
+    };
+
+    let mut search = LocationSearch::new(composed, &almanac.seed_ranges());
+    let locations: Vec<u64> = search.by_ref().take(count).collect();
+
+    println!("locations: {:?}", locations);
+    println!("seed ranges mapped: {}", search.splits());
+}
+
+// prints the location of the seed at a position in the seed ranges
+fn print_nth_seed(almanac: &Almanac, composed: &PiecewiseMap, args: &[String]) {
+    let position = match args {
+        [position] => position
+            .parse::<u64>()
+            .expect("Position should be a number"),
+        _ => panic!("Expecting the position of a seed"),
+    };
+
+    match almanac.nth_seed(position) {
+        Some(seed) => println!("seed {}: location {}", seed, composed.map(seed)),
+        None => {
+            eprintln!("No seed at position {}", position);
+            std::process::exit(1);
+        }
+    }
+}
+
+// prints an SVG diagram of the seed ranges going through every map
+fn print_diagram(almanac: &Almanac) {
+    let route = route_or_exit(almanac, "seed", "location");
+    let operations = route
+        .forward_operations()
+        .expect("Seeds should lead forward to locations");
+
+    print!(
+        "{}",
+        Diagram::new(operations, almanac.listed_seed_ranges().collect()).svg()
+    );
+}
+
+// prints a report for each map and for the chain from seeds to locations, failing if any is broken
+fn print_validation(almanac: &Almanac) {
+    let reports = almanac.validate();
+
+    for report in reports.iter() {
+        print!("{}", report);
+    }
+
+    // a broken chain is one more finding, the maps are reported all the same
+    let chained = match almanac.route("seed", "location") {
+        Ok(route) if route.forward_operations().is_some() => {
+            println!("seed-to-location: {}", route.categories().join(" -> "));
+            true
+        }
+        Ok(_) => {
+            println!("seed-to-location: broken, maps only lead from locations back to seeds");
+            false
+        }
+        Err(error) => {
+            println!("seed-to-location: broken, {}", error);
+            false
+        }
+    };
+
+    if !chained || !reports.iter().all(MapReport::is_clean) {
+        std::process::exit(1);
+    }
+}
+
 fn main() {
     let input = include_str!("../etc/input");
 
     let almanac = Almanac::from(input);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // validate lists ignored lines in its report, other commands warn about them
+    if args.first().map(String::as_str) != Some("validate") {
+        warn_rejected(&almanac);
+    }
 
-    println!("{}", ex1(&almanac));
-    println!("{}", ex2(&almanac));
+    match args.first().map(|command| command.as_str()) {
+        Some("table") => print!("{}", compose_or_exit(&almanac)),
+        Some("route") => print_route(&almanac, &args[1..]),
+        Some("inverse") => print_inverse(&almanac, &args[1..]),
+        Some("validate") => print_validation(&almanac),
+        Some("diagram") => print_diagram(&almanac),
+        Some("smallest") => print_smallest(&almanac, &compose_or_exit(&almanac), &args[1..]),
+        Some("seed") => print_nth_seed(&almanac, &compose_or_exit(&almanac), &args[1..]),
+        _ => {
+            let composed = compose_or_exit(&almanac);
+
+            println!("{}", ex1(&almanac, &composed));
+            println!("{}", ex2(&almanac, &composed));
+        }
+    }
 }
@@ -0,0 +1,214 @@
+use std::{fmt, ops::RangeInclusive};
+
+use intervals::IntervalSet;
+
+use crate::Operation;
+
+// numbers from `start` up to the start of the next piece are shifted by `offset`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Piece {
+    start: u64,
+    offset: i128,
+}
+
+impl Piece {
+    fn shift(&self, number: u64) -> u64 {
+        (number as i128 + self.offset) as u64
+    }
+}
+
+/// A function over every u64, shifting each interval between breakpoints by its own offset
+#[derive(Debug, Clone, PartialEq)]
+pub struct PiecewiseMap {
+    // sorted by start, the first one starting at 0
+    pieces: Vec<Piece>,
+}
+
+impl PiecewiseMap {
+    pub fn identity() -> PiecewiseMap {
+        PiecewiseMap {
+            pieces: vec![Piece {
+                start: 0,
+                offset: 0,
+            }],
+        }
+    }
+
+    // merges consecutive pieces with the same offset
+    fn normalize(pieces: Vec<Piece>) -> PiecewiseMap {
+        let mut normalized: Vec<Piece> = Vec::with_capacity(pieces.len());
+
+        for piece in pieces {
+            match normalized.last() {
+                Some(last) if last.offset == piece.offset => {}
+                _ => normalized.push(piece),
+            }
+        }
+
+        PiecewiseMap { pieces: normalized }
+    }
+
+    fn piece_end(&self, piece_index: usize) -> u64 {
+        self.pieces
+            .get(piece_index + 1)
+            .map(|next_piece| next_piece.start - 1)
+            .unwrap_or(u64::MAX)
+    }
+
+    fn piece_index_of(&self, number: u64) -> usize {
+        self.pieces.partition_point(|piece| piece.start <= number) - 1
+    }
+
+    pub fn map(&self, number: u64) -> u64 {
+        self.pieces[self.piece_index_of(number)].shift(number)
+    }
+
+    // each part of a range lying in a single piece, with that piece
+    fn split(&self, range: &RangeInclusive<u64>) -> Vec<(RangeInclusive<u64>, Piece)> {
+        let mut parts = Vec::new();
+        let mut piece_index = self.piece_index_of(*range.start());
+        let mut start = *range.start();
+
+        loop {
+            let end = self.piece_end(piece_index).min(*range.end());
+            parts.push((start..=end, self.pieces[piece_index]));
+
+            if end == *range.end() {
+                return parts;
+            }
+
+            start = end + 1;
+            piece_index += 1;
+        }
+    }
+
+    pub fn map_range(&self, range: &RangeInclusive<u64>) -> IntervalSet {
+        if range.is_empty() {
+            return IntervalSet::new();
+        }
+
+        self.split(range)
+            .into_iter()
+            .map(|(part, piece)| piece.shift(*part.start())..=piece.shift(*part.end()))
+            .collect()
+    }
+
+    /// Splits a non-empty range into the image of its part lying in a single piece, and the rest
+    pub fn split_first(
+        &self,
+        range: &RangeInclusive<u64>,
+    ) -> (RangeInclusive<u64>, Option<RangeInclusive<u64>>) {
+        let piece_index = self.piece_index_of(*range.start());
+        let piece = self.pieces[piece_index];
+        let end = self.piece_end(piece_index).min(*range.end());
+
+        let rest = if end < *range.end() {
+            Some(end + 1..=*range.end())
+        } else {
+            None
+        };
+
+        (piece.shift(*range.start())..=piece.shift(end), rest)
+    }
+
+    /// No number of a non-empty range is mapped below this one
+    pub fn lower_bound(&self, range: &RangeInclusive<u64>) -> u64 {
+        let first_index = self.piece_index_of(*range.start());
+        let last_index = self.piece_index_of(*range.end());
+
+        // each piece reaches its lowest location at the first number of the range it holds
+        self.pieces[first_index..=last_index]
+            .iter()
+            .map(|piece| piece.shift(piece.start.max(*range.start())))
+            .min()
+            .expect("A range should lie in at least one piece")
+    }
+
+    pub fn map_ranges(&self, numbers: &IntervalSet) -> IntervalSet {
+        numbers
+            .iter()
+            .flat_map(|range| self.map_range(range))
+            .collect()
+    }
+
+    /// Returns the function applying this one, then the other one
+    pub fn then(&self, other: &PiecewiseMap) -> PiecewiseMap {
+        let mut pieces = Vec::new();
+
+        for (piece_index, piece) in self.pieces.iter().enumerate() {
+            let image = piece.shift(piece.start)..=piece.shift(self.piece_end(piece_index));
+
+            // the image of a piece can be cut by the breakpoints of the other function
+            for (image_part, other_piece) in other.split(&image) {
+                pieces.push(Piece {
+                    start: (*image_part.start() as i128 - piece.offset) as u64,
+                    offset: piece.offset + other_piece.offset,
+                });
+            }
+        }
+
+        PiecewiseMap::normalize(pieces)
+    }
+}
+
+impl From<&Operation<'_>> for PiecewiseMap {
+    fn from(operation: &Operation<'_>) -> Self {
+        let mut pieces = Vec::new();
+        // first number not covered by the mappers seen so far
+        let mut uncovered_start = 0u64;
+
+        for mapper in operation.mappers.iter() {
+            let start = uncovered_start.max(mapper.src_start);
+
+            if start > mapper.src_end {
+                continue;
+            }
+
+            if uncovered_start < start {
+                pieces.push(Piece {
+                    start: uncovered_start,
+                    offset: 0,
+                });
+            }
+
+            pieces.push(Piece {
+                start,
+                offset: mapper.offset(),
+            });
+
+            match mapper.src_end.checked_add(1) {
+                Some(next_start) => uncovered_start = next_start,
+                // the mapper covers numbers up to the very last one
+                None => return PiecewiseMap::normalize(pieces),
+            }
+        }
+
+        pieces.push(Piece {
+            start: uncovered_start,
+            offset: 0,
+        });
+
+        PiecewiseMap::normalize(pieces)
+    }
+}
+
+/// One line per piece: source range, destination range and offset
+impl fmt::Display for PiecewiseMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (piece_index, piece) in self.pieces.iter().enumerate() {
+            let end = self.piece_end(piece_index);
+
+            writeln!(
+                f,
+                "{}..={} -> {}..={} ({:+})",
+                piece.start,
+                end,
+                piece.shift(piece.start),
+                piece.shift(end),
+                piece.offset
+            )?;
+        }
+
+        Ok(())
+    }
+}
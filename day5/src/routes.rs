@@ -0,0 +1,181 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use intervals::IntervalSet;
+
+use crate::{piecewise::PiecewiseMap, Operation};
+
+#[derive(Debug, PartialEq)]
+pub enum RouteError {
+    UnknownCategory(String),
+    // several maps start from the same category
+    AmbiguousCategory(String),
+    Cycle(String),
+    NoRoute { from: String, to: String },
+}
+
+impl fmt::Display for RouteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RouteError::UnknownCategory(category) => {
+                write!(f, "no map reads or writes category '{}'", category)
+            }
+            RouteError::AmbiguousCategory(category) => {
+                write!(f, "several maps start from category '{}'", category)
+            }
+            RouteError::Cycle(category) => {
+                write!(f, "maps loop back to category '{}'", category)
+            }
+            RouteError::NoRoute { from, to } => {
+                write!(f, "no chain of maps between '{}' and '{}'", from, to)
+            }
+        }
+    }
+}
+
+/// Maps linking two categories, in the order they are applied
+#[derive(Debug)]
+pub struct Route<'r, 'a> {
+    operations: Vec<&'r Operation<'a>>,
+    // going from destinations back to sources
+    backward: bool,
+}
+
+impl<'r, 'a> Route<'r, 'a> {
+    pub fn map_ranges(&self, numbers: &IntervalSet) -> IntervalSet {
+        self.operations
+            .iter()
+            .fold(numbers.clone(), |numbers, operation| {
+                if self.backward {
+                    operation.inverse_ranges(&numbers)
+                } else {
+                    PiecewiseMap::from(*operation).map_ranges(&numbers)
+                }
+            })
+    }
+
+    /// Maps in the order they are applied, which can only be used as they are going forward
+    pub fn forward_operations(&self) -> Option<&[&'r Operation<'a>]> {
+        if self.backward {
+            None
+        } else {
+            Some(&self.operations)
+        }
+    }
+
+    /// Composes the route into a single function, which only exists going forward
+    pub fn compose(&self) -> Option<PiecewiseMap> {
+        self.forward_operations().map(|operations| {
+            operations
+                .iter()
+                .fold(PiecewiseMap::identity(), |composed, operation| {
+                    composed.then(&PiecewiseMap::from(*operation))
+                })
+        })
+    }
+
+    /// Categories visited along the route, both ends included
+    pub fn categories(&self) -> Vec<&'a str> {
+        let mut categories = Vec::with_capacity(self.operations.len() + 1);
+
+        for operation in self.operations.iter() {
+            if self.backward {
+                categories.push(operation.destination);
+            } else {
+                categories.push(operation.source);
+            }
+        }
+
+        match (self.operations.last(), self.backward) {
+            (Some(operation), false) => categories.push(operation.destination),
+            (Some(operation), true) => categories.push(operation.source),
+            (None, _) => {}
+        }
+
+        categories
+    }
+}
+
+// follows the single map leaving each category, until reaching `to` or a dead end
+fn follow<'r, 'a>(
+    maps_by_source: &HashMap<&str, Vec<&'r Operation<'a>>>,
+    from: &str,
+    to: &str,
+) -> Result<Option<Vec<&'r Operation<'a>>>, RouteError> {
+    let mut chain = Vec::new();
+    let mut visited = HashSet::from([from]);
+    let mut category = from;
+
+    while category != to {
+        let operation = match maps_by_source.get(category).map(Vec::as_slice) {
+            None => return Ok(None),
+            Some([operation]) => *operation,
+            Some(_) => return Err(RouteError::AmbiguousCategory(category.to_string())),
+        };
+
+        chain.push(operation);
+        category = operation.destination;
+
+        if !visited.insert(category) {
+            return Err(RouteError::Cycle(category.to_string()));
+        }
+    }
+
+    Ok(Some(chain))
+}
+
+/// Finds the maps leading from one category to another, following them backward if needed
+pub fn find<'r, 'a>(
+    operations: &'r [Operation<'a>],
+    from: &str,
+    to: &str,
+) -> Result<Route<'r, 'a>, RouteError> {
+    let mut maps_by_source: HashMap<&str, Vec<&Operation>> = HashMap::new();
+    let mut categories = HashSet::new();
+
+    for operation in operations.iter() {
+        maps_by_source
+            .entry(operation.source)
+            .or_default()
+            .push(operation);
+        categories.insert(operation.source);
+        categories.insert(operation.destination);
+    }
+
+    for category in [from, to] {
+        if !categories.contains(category) {
+            return Err(RouteError::UnknownCategory(category.to_string()));
+        }
+    }
+
+    let forward = follow(&maps_by_source, from, to);
+
+    if let Ok(Some(operations)) = forward {
+        return Ok(Route {
+            operations,
+            backward: false,
+        });
+    }
+
+    let backward = follow(&maps_by_source, to, from);
+
+    if let Ok(Some(mut operations)) = backward {
+        operations.reverse();
+
+        return Ok(Route {
+            operations,
+            backward: true,
+        });
+    }
+
+    // reporting a broken graph rather than a mere dead end
+    match (forward, backward) {
+        (Err(error), _) | (_, Err(error)) => Err(error),
+        _ => Err(RouteError::NoRoute {
+            from: from.to_string(),
+            to: to.to_string(),
+        }),
+    }
+}
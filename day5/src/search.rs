@@ -0,0 +1,98 @@
+use std::{cmp::Reverse, collections::BinaryHeap, ops::RangeInclusive};
+
+use intervals::IntervalSet;
+
+use crate::piecewise::PiecewiseMap;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Pending {
+    // locations from the bound up to `end`, all reached
+    Locations { end: u64 },
+    // seeds not mapped yet, whose locations are all above the bound
+    Seeds { start: u64, end: u64 },
+}
+
+/// Locations reached by seed ranges, lazily produced from the smallest one
+///
+/// Seed ranges are split one piece at a time, in order of the lowest location they could reach,
+/// so ranges that cannot beat the locations already produced are never mapped.
+pub struct LocationSearch<'m> {
+    map: &'m PiecewiseMap,
+    pending: BinaryHeap<Reverse<(u64, Pending)>>,
+    last: Option<u64>,
+    splits: usize,
+}
+
+impl<'m> LocationSearch<'m> {
+    pub fn new(map: &'m PiecewiseMap, seeds: &IntervalSet) -> LocationSearch<'m> {
+        let mut search = LocationSearch {
+            map,
+            pending: BinaryHeap::new(),
+            last: None,
+            splits: 0,
+        };
+
+        for range in seeds.iter() {
+            search.push_seeds(range.clone());
+        }
+
+        search
+    }
+
+    fn push_seeds(&mut self, range: RangeInclusive<u64>) {
+        let bound = self.map.lower_bound(&range);
+
+        self.pending.push(Reverse((
+            bound,
+            Pending::Seeds {
+                start: *range.start(),
+                end: *range.end(),
+            },
+        )));
+    }
+
+    /// Number of seed ranges mapped so far
+    pub fn splits(&self) -> usize {
+        self.splits
+    }
+}
+
+impl Iterator for LocationSearch<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        while let Some(Reverse((bound, pending))) = self.pending.pop() {
+            match pending {
+                Pending::Locations { end } => {
+                    if bound < end {
+                        self.pending
+                            .push(Reverse((bound + 1, Pending::Locations { end })));
+                    }
+
+                    // overlapping location ranges reach some locations twice
+                    if self.last != Some(bound) {
+                        self.last = Some(bound);
+                        return Some(bound);
+                    }
+                }
+                Pending::Seeds { start, end } => {
+                    let (locations, rest) = self.map.split_first(&(start..=end));
+                    self.splits += 1;
+
+                    self.pending.push(Reverse((
+                        *locations.start(),
+                        Pending::Locations {
+                            end: *locations.end(),
+                        },
+                    )));
+
+                    if let Some(rest) = rest {
+                        self.push_seeds(rest);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,92 @@
+use std::{fmt, ops::RangeInclusive};
+
+use intervals::IntervalSet;
+
+use crate::Operation;
+
+/// Source numbers claimed by two mappers of the same map
+#[derive(Debug, PartialEq)]
+pub struct Overlap {
+    // the mapper starting first, which is the one applied
+    applied: RangeInclusive<u64>,
+    shadowed: RangeInclusive<u64>,
+    shared: RangeInclusive<u64>,
+}
+
+/// Problems found in one map, and the source numbers it leaves unchanged
+#[derive(Debug)]
+pub struct MapReport<'r, 'a> {
+    operation: &'r Operation<'a>,
+    overlaps: Vec<Overlap>,
+    identity: IntervalSet,
+}
+
+impl MapReport<'_, '_> {
+    pub fn is_clean(&self) -> bool {
+        self.operation.rejected.is_empty() && self.overlaps.is_empty()
+    }
+}
+
+fn overlaps(operation: &Operation) -> Vec<Overlap> {
+    let mut overlaps = Vec::new();
+    // source ranges of the mappers seen so far that may still reach the next ones
+    let mut reaching: Vec<RangeInclusive<u64>> = Vec::new();
+
+    for mapper in operation.mappers.iter() {
+        let range = mapper.src_range();
+
+        // mappers are sorted by start, a range ending before this one cannot reach any later
+        reaching.retain(|applied| applied.end() >= range.start());
+
+        for applied in reaching.iter() {
+            overlaps.push(Overlap {
+                applied: applied.clone(),
+                shadowed: range.clone(),
+                shared: *range.start()..=*range.end().min(applied.end()),
+            });
+        }
+
+        reaching.push(range);
+    }
+
+    overlaps
+}
+
+pub fn validate<'r, 'a>(operation: &'r Operation<'a>) -> MapReport<'r, 'a> {
+    MapReport {
+        operation,
+        overlaps: overlaps(operation),
+        identity: IntervalSet::from(0..=u64::MAX).difference(&operation.mapped_sources()),
+    }
+}
+
+impl fmt::Display for MapReport<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}-to-{}: {} mappers",
+            self.operation.source,
+            self.operation.destination,
+            self.operation.mappers.len()
+        )?;
+
+        for error in self.operation.rejected.iter() {
+            writeln!(f, "  ignored: {}", error)?;
+        }
+
+        for overlap in self.overlaps.iter() {
+            writeln!(
+                f,
+                "  overlap: {:?} shadows {:?} on {:?}",
+                overlap.applied, overlap.shadowed, overlap.shared
+            )?;
+        }
+
+        writeln!(
+            f,
+            "  identity: {} ({} numbers)",
+            self.identity,
+            self.identity.count()
+        )
+    }
+}
@@ -1,92 +1,53 @@
-use std::ops::RangeInclusive;
-
+use models::RaceModel;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use polynomial::Polynomial;
 use regex::Regex;
 
+mod models;
+mod polynomial;
+
 #[derive(Debug)]
 struct Race {
     time: u64,
     distance: u64,
 }
 
-impl Race {
-    fn pressing_times_beating_distance(&self) -> Option<RangeInclusive<u64>> {
-        match self.equation_polynom().solve_root() {
-            PolynomSolution::NoSolution => None,
-            PolynomSolution::Solution(_) => None,
-            PolynomSolution::TwoSolution(first_sol, second_sol) => Some(RangeInclusive::new(
-                (first_sol + 1f64).floor() as u64,
-                (second_sol - 1f64).ceil() as u64,
-            )),
-        }
-    }
-
-    fn equation_polynom(&self) -> Polynom {
-        // distance formula is: (race_time - time_we_press) * time_we_press
-        // distance of beat is race_record
-        // equation is then (race_time - time_we_press) * time_we_press > race_record
-        // equation is then -time_we_press^2 + time_we_press*race_time - race_record > 0
-        // if what we want to solve is the time_we_press, then polynom becomes
-        // -x^2 + race_time*x - race_record > 0
-        Polynom {
-            a: -1f64,
-            b: self.time as f64,
-            c: -(self.distance as f64),
-        }
-    }
-}
-
-#[derive(Debug)]
-struct Polynom {
-    a: f64,
-    b: f64,
-    c: f64,
-}
-
-#[derive(Debug)]
-enum PolynomSolution {
-    NoSolution,
-    Solution(f64),
-    TwoSolution(f64, f64),
-}
-
-impl Polynom {
-    fn solve_root(&self) -> PolynomSolution {
-        let discriminant = self.b * self.b - 4f64 * self.a * self.c;
-
-        match discriminant.total_cmp(&0f64) {
-            std::cmp::Ordering::Less => PolynomSolution::NoSolution,
-            std::cmp::Ordering::Equal => PolynomSolution::Solution(-self.b / (2f64 * self.a)),
-            std::cmp::Ordering::Greater => PolynomSolution::TwoSolution(
-                (-self.b + discriminant.sqrt()) / (2f64 * self.a),
-                (-self.b - discriminant.sqrt()) / (2f64 * self.a),
-            ),
-        }
-    }
+/// How the numbers of a sheet line are read
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Reading {
+    // one race per column
+    Columns,
+    // the spaces between digits are bad kerning, there is a single race
+    Kerned,
 }
 
-fn parse_numbers(line: &str) -> Vec<u64> {
+fn parse_numbers(line: &str, reading: Reading) -> Vec<u64> {
     let number_re = Regex::new(r"((\d+)\D*)").unwrap();
 
-    number_re
+    let digit_groups = number_re
         .captures_iter(line)
-        .map(|capture| {
-            let capture_match = capture.get(2).expect("Should have inner number");
-            capture_match
-                .as_str()
-                .parse::<u64>()
-                .expect("Number should be a number")
-        })
+        .map(|capture| capture.get(2).expect("Should have inner number").as_str());
+
+    let numbers: Vec<String> = match reading {
+        Reading::Columns => digit_groups.map(str::to_string).collect(),
+        Reading::Kerned => vec![digit_groups.collect()],
+    };
+
+    numbers
+        .iter()
+        .map(|number| number.parse::<u64>().expect("Number should be a number"))
         .collect()
 }
 
-fn parse(input: &str) -> Vec<Race> {
+fn parse(input: &str, reading: Reading) -> Vec<Race> {
     let mut lines = input.lines();
 
     let times_line = lines.next().expect("expect a time description");
-    let times = parse_numbers(times_line);
+    let times = parse_numbers(times_line, reading);
 
     let distances_line = lines.next().expect("expect a distance description");
-    let distances = parse_numbers(distances_line);
+    let distances = parse_numbers(distances_line, reading);
 
     if distances.len() != times.len() {
         panic!("There should have been the same number of distances and times");
@@ -102,39 +63,171 @@ fn parse(input: &str) -> Vec<Race> {
         .collect()
 }
 
-fn ex1() -> u64 {
-    let input = include_str!("../etc/input");
+// a race whose record cannot be beaten has no way to win
+fn ways_to_win(race: &Race, model: &dyn RaceModel) -> u64 {
+    models::winning_hold_times(model, race)
+        .iter()
+        .map(|range| range.end() - range.start() + 1)
+        .sum()
+}
 
-    let races = parse(input);
-    races.iter().fold(1, |accumulator, race| {
-        let multiplier = match race.pressing_times_beating_distance() {
-            Some(range) => range.end() - range.start() + 1,
-            None => 1,
-        };
+fn ways_to_win_product(races: &[Race], model: &dyn RaceModel) -> u64 {
+    races.iter().map(|race| ways_to_win(race, model)).product()
+}
 
-        accumulator * multiplier
-    })
+fn ex1(input: &str, model: &dyn RaceModel) -> u64 {
+    ways_to_win_product(&parse(input, Reading::Columns), model)
 }
 
-fn ex2() -> u64 {
-    let input = include_str!("../etc/input_fixed");
+fn ex2(input: &str, model: &dyn RaceModel) -> u64 {
+    ways_to_win_product(&parse(input, Reading::Kerned), model)
+}
 
-    let races = parse(input);
-    races.iter().fold(1, |accumulator, race| {
-        let multiplier = match race.pressing_times_beating_distance() {
-            Some(range) => range.end() - range.start() + 1,
-            None => 1,
-        };
+// prints the pressing times beating the record of a single race
+fn print_race(args: &[String], model: &dyn RaceModel) {
+    let race = match args {
+        [time, distance] => Race {
+            time: time.parse::<u64>().expect("Time should be a number"),
+            distance: distance
+                .parse::<u64>()
+                .expect("Distance should be a number"),
+        },
+        _ => panic!("Expecting a race time and a record distance"),
+    };
+
+    let best = models::best_hold_time(model, race.time);
+    println!(
+        "best hold time: {} ({:.1} distance)",
+        best,
+        model.distance(best, race.time)
+    );
+
+    let hold_times = models::winning_hold_times(model, &race);
+
+    if hold_times.is_empty() {
+        println!("record cannot be beaten");
+    }
+
+    for range in hold_times.iter() {
+        println!("{:?}", range);
+    }
+
+    println!("{} ways", ways_to_win(&race, model));
+}
+
+// prints the roots of a polynomial and where it is positive over an integer range
+fn print_polynomial(args: &[String]) {
+    let [first, last, coefficients @ ..] = args else {
+        panic!("Expecting a range of integers then coefficients, the highest degree first");
+    };
+
+    let parse_integer = |integer: &String| {
+        integer
+            .parse::<BigInt>()
+            .expect("Range bound should be an integer")
+    };
+    let polynomial = Polynomial::new(
+        coefficients
+            .iter()
+            .rev()
+            .map(|coefficient| {
+                coefficient
+                    .parse::<BigRational>()
+                    .expect("Coefficient should be a rational like 3 or -3/4")
+            })
+            .collect(),
+    );
+
+    println!("p(x) = {}", polynomial);
+    println!("p'(x) = {}", polynomial.derivative());
+
+    for root in polynomial.isolate_roots(&BigRational::new(1.into(), 1000.into())) {
+        println!("root: {}", root);
+    }
 
-        accumulator * multiplier
-    })
+    let solutions: Vec<String> = polynomial
+        .positive_integers(parse_integer(first)..=parse_integer(last))
+        .iter()
+        .map(|range| format!("{}..={}", range.start(), range.end()))
+        .collect();
+
+    println!("p(x) > 0 for x in {{{}}}", solutions.join(", "));
 }
 
 fn main() {
-    let input = include_str!("../etc/input_fixed");
+    let input = include_str!("../etc/input");
 
-    let races = parse(input);
+    let mut model = models::parse_model("linear");
+    let mut args: Vec<String> = Vec::new();
+    let mut args_it = std::env::args().skip(1);
 
-    println!("{}", ex1());
-    println!("{}", ex2());
+    while let Some(arg) = args_it.next() {
+        match arg.as_str() {
+            "--model" => {
+                model = models::parse_model(&args_it.next().expect("--model should have a value"))
+            }
+            _ => args.push(arg),
+        }
+    }
+
+    match args.first().map(|command| command.as_str()) {
+        Some("race") => print_race(&args[1..], model.as_ref()),
+        Some("polynomial") => print_polynomial(&args[1..]),
+        Some(command) => panic!("Unknown command '{}'", command),
+        None => {
+            println!("{}", ex1(input, model.as_ref()));
+            println!("{}", ex2(input, model.as_ref()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use models::LinearCharging;
+
+    const LINEAR: LinearCharging = LinearCharging { rate: 1 };
+
+    #[test]
+    fn unbeatable_record_has_no_way_to_win() {
+        let race = Race {
+            time: 4,
+            distance: 4,
+        };
+
+        assert_eq!(ways_to_win(&race, &LINEAR), 0);
+        assert_eq!(ways_to_win_product(&[race], &LINEAR), 0);
+    }
+
+    #[test]
+    fn sample_races() {
+        let races = [
+            Race {
+                time: 7,
+                distance: 9,
+            },
+            Race {
+                time: 30,
+                distance: 200,
+            },
+        ];
+
+        assert_eq!(ways_to_win(&races[0], &LINEAR), 4);
+        assert_eq!(ways_to_win(&races[1], &LINEAR), 9);
+        assert_eq!(ways_to_win_product(&races, &LINEAR), 36);
+    }
+
+    #[test]
+    fn race_near_u64_max() {
+        let race = Race {
+            time: u64::MAX,
+            distance: 0,
+        };
+
+        assert_eq!(
+            models::winning_hold_times(&LINEAR, &race),
+            vec![1..=u64::MAX - 1]
+        );
+        assert_eq!(ways_to_win(&race, &LINEAR), u64::MAX - 1);
+    }
 }
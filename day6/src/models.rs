@@ -0,0 +1,249 @@
+use std::ops::RangeInclusive;
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::ToPrimitive;
+
+use crate::{polynomial::Polynomial, Race};
+
+/// How far a boat goes depending on how long its button is held
+pub trait RaceModel {
+    /// Distance covered over the race, which should rise then fall as the hold time grows
+    fn distance(&self, hold_time: u64, race_time: u64) -> f64;
+
+    /// The distance as polynomials in the hold time, each one over its own range of hold times
+    fn polynomial_pieces(&self, _race_time: u64) -> Option<Vec<(RangeInclusive<u64>, Polynomial)>> {
+        None
+    }
+}
+
+/// Each millisecond held adds `rate` to the speed, kept until the end of the race
+pub struct LinearCharging {
+    pub rate: u64,
+}
+
+impl RaceModel for LinearCharging {
+    fn distance(&self, hold_time: u64, race_time: u64) -> f64 {
+        self.rate as f64 * hold_time as f64 * race_time.saturating_sub(hold_time) as f64
+    }
+
+    fn polynomial_pieces(&self, race_time: u64) -> Option<Vec<(RangeInclusive<u64>, Polynomial)>> {
+        // distance formula is: (race_time - time_we_press) * time_we_press * rate
+        // if what we want to solve is the time_we_press, then polynomial becomes
+        // -rate*x^2 + rate*race_time*x
+        let rate = BigInt::from(self.rate);
+        let polynomial =
+            Polynomial::from_integers(&[BigInt::from(0), &rate * BigInt::from(race_time), -rate]);
+
+        Some(vec![(0..=race_time, polynomial)])
+    }
+}
+
+/// Like linear charging, but the speed cannot go over a top speed
+pub struct CappedSpeed {
+    pub rate: u64,
+    pub top_speed: u64,
+}
+
+impl CappedSpeed {
+    // shortest hold time reaching the top speed
+    fn capping_hold_time(&self) -> Option<u64> {
+        if self.rate == 0 {
+            None
+        } else {
+            Some(self.top_speed.div_ceil(self.rate))
+        }
+    }
+}
+
+impl RaceModel for CappedSpeed {
+    fn distance(&self, hold_time: u64, race_time: u64) -> f64 {
+        let speed = (self.rate as f64 * hold_time as f64).min(self.top_speed as f64);
+
+        speed * race_time.saturating_sub(hold_time) as f64
+    }
+
+    fn polynomial_pieces(&self, race_time: u64) -> Option<Vec<(RangeInclusive<u64>, Polynomial)>> {
+        let charging = LinearCharging { rate: self.rate }
+            .polynomial_pieces(race_time)?
+            .remove(0)
+            .1;
+
+        let capping_hold_time = match self.capping_hold_time() {
+            Some(hold_time) if hold_time <= race_time => hold_time,
+            // the top speed is never reached
+            _ => return Some(vec![(0..=race_time, charging)]),
+        };
+
+        // top_speed * (race_time - time_we_press)
+        let top_speed = BigInt::from(self.top_speed);
+        let capped = Polynomial::from_integers(&[&top_speed * BigInt::from(race_time), -top_speed]);
+
+        let mut pieces = vec![(capping_hold_time..=race_time, capped)];
+
+        if capping_hold_time > 0 {
+            pieces.insert(0, (0..=capping_hold_time - 1, charging));
+        }
+
+        Some(pieces)
+    }
+}
+
+/// Holding sets the acceleration of the boat, which is slowed down by a drag proportional to
+/// its speed once released
+pub struct Drag {
+    pub rate: f64,
+    pub drag: f64,
+}
+
+impl RaceModel for Drag {
+    fn distance(&self, hold_time: u64, race_time: u64) -> f64 {
+        let acceleration = self.rate * hold_time as f64;
+        let moving_time = race_time.saturating_sub(hold_time) as f64;
+
+        if self.drag == 0.0 {
+            return acceleration * moving_time * moving_time / 2.0;
+        }
+
+        // speed is acceleration / drag * (1 - e^(-drag * t)), integrated over the moving time
+        let decay = (-self.drag * moving_time).exp_m1();
+
+        acceleration / self.drag * (moving_time + decay / self.drag)
+    }
+}
+
+// smallest value of a range for which a predicate holds, given it keeps holding after that
+fn first_holding(range: RangeInclusive<u64>, predicate: impl Fn(u64) -> bool) -> Option<u64> {
+    let (mut low, mut high) = range.into_inner();
+
+    if !predicate(high) {
+        return None;
+    }
+
+    while low < high {
+        let middle = low + (high - low) / 2;
+
+        if predicate(middle) {
+            high = middle;
+        } else {
+            low = middle + 1;
+        }
+    }
+
+    Some(low)
+}
+
+/// Hold time covering the longest distance, found by ternary search as the distance is unimodal
+pub fn best_hold_time(model: &dyn RaceModel, race_time: u64) -> u64 {
+    let distance = |hold_time| model.distance(hold_time, race_time);
+    let (mut low, mut high) = (0, race_time);
+
+    while high - low > 2 {
+        let first_third = low + (high - low) / 3;
+        let second_third = high - (high - low) / 3;
+
+        if distance(first_third) < distance(second_third) {
+            low = first_third + 1;
+        } else if distance(first_third) > distance(second_third) {
+            high = second_third - 1;
+        } else {
+            (low, high) = (first_third, second_third);
+        }
+    }
+
+    (low..=high)
+        .max_by(|left, right| distance(*left).total_cmp(&distance(*right)))
+        .expect("Search range should not be empty")
+}
+
+// the winning hold times on each side of the best one, when only distances can be computed
+fn numerical_hold_times(model: &dyn RaceModel, race: &Race) -> Vec<RangeInclusive<u64>> {
+    let record = race.distance as f64;
+    let beats_record = |hold_time| model.distance(hold_time, race.time) > record;
+    let best = best_hold_time(model, race.time);
+
+    if !beats_record(best) {
+        return Vec::new();
+    }
+
+    let first = first_holding(0..=best, beats_record).expect("Best hold time beats the record");
+    let last = match first_holding(best..=race.time, |hold_time| !beats_record(hold_time)) {
+        Some(losing) => losing - 1,
+        None => race.time,
+    };
+
+    vec![first..=last]
+}
+
+/// Hold times beating the record of a race, solved exactly when the model has polynomial pieces
+pub fn winning_hold_times(model: &dyn RaceModel, race: &Race) -> Vec<RangeInclusive<u64>> {
+    let pieces = match model.polynomial_pieces(race.time) {
+        Some(pieces) => pieces,
+        None => return numerical_hold_times(model, race),
+    };
+
+    let record = BigRational::from_integer(BigInt::from(race.distance));
+    let to_u64 = |hold_time: &BigInt| {
+        hold_time
+            .to_u64()
+            .expect("Hold time should not exceed race time")
+    };
+
+    let mut hold_times: Vec<RangeInclusive<u64>> = Vec::new();
+
+    for (range, polynomial) in pieces {
+        let (first, last) = range.into_inner();
+        let solutions = polynomial
+            .minus_constant(&record)
+            .positive_integers(BigInt::from(first)..=BigInt::from(last));
+
+        for solution in solutions {
+            let (start, end) = (to_u64(solution.start()), to_u64(solution.end()));
+
+            // joining solutions continuing over the next piece
+            match hold_times.last_mut() {
+                Some(previous) if *previous.end() + 1 == start => {
+                    *previous = *previous.start()..=end
+                }
+                _ => hold_times.push(start..=end),
+            }
+        }
+    }
+
+    hold_times
+}
+
+/// Reads a model like `linear`, `linear:2`, `capped:2,30` or `drag:1.5,0.01`
+///
+/// The charging rate of `capped` and `drag` defaults to 1 when only the last parameter is given,
+/// as in `capped:30` or `drag:0.01`.
+pub fn parse_model(description: &str) -> Box<dyn RaceModel> {
+    let (name, parameters) = match description.split_once(':') {
+        Some((name, parameters)) => (name, parameters.split(',').collect()),
+        None => (description, Vec::new()),
+    };
+
+    match (name, parameters.as_slice()) {
+        ("linear", []) => Box::new(LinearCharging { rate: 1 }),
+        ("linear", [rate]) => Box::new(LinearCharging {
+            rate: rate.parse().expect("Charging rate should be a number"),
+        }),
+        ("capped", [top_speed]) => Box::new(CappedSpeed {
+            rate: 1,
+            top_speed: top_speed.parse().expect("Top speed should be a number"),
+        }),
+        ("capped", [rate, top_speed]) => Box::new(CappedSpeed {
+            rate: rate.parse().expect("Charging rate should be a number"),
+            top_speed: top_speed.parse().expect("Top speed should be a number"),
+        }),
+        ("drag", [drag]) => Box::new(Drag {
+            rate: 1.0,
+            drag: drag.parse().expect("Drag should be a number"),
+        }),
+        ("drag", [rate, drag]) => Box::new(Drag {
+            rate: rate.parse().expect("Charging rate should be a number"),
+            drag: drag.parse().expect("Drag should be a number"),
+        }),
+        _ => panic!("Unknown model '{}'", description),
+    }
+}
@@ -0,0 +1,352 @@
+use std::{collections::BTreeSet, fmt, ops::RangeInclusive};
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Signed, Zero};
+
+/// A polynomial with rational coefficients
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial {
+    // lowest degree first, without trailing zeros
+    coefficients: Vec<BigRational>,
+}
+
+/// A real root, known exactly or as the only root of an interval `(low, high]`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Root {
+    Exact(BigRational),
+    Between(BigRational, BigRational),
+}
+
+fn rational(value: i128) -> BigRational {
+    BigRational::from_integer(BigInt::from(value))
+}
+
+impl Polynomial {
+    pub fn new(mut coefficients: Vec<BigRational>) -> Polynomial {
+        while coefficients.last().is_some_and(Zero::is_zero) {
+            coefficients.pop();
+        }
+
+        Polynomial { coefficients }
+    }
+
+    /// Builds a polynomial from integer coefficients, the lowest degree first
+    pub fn from_integers(coefficients: &[BigInt]) -> Polynomial {
+        Polynomial::new(
+            coefficients
+                .iter()
+                .cloned()
+                .map(BigRational::from_integer)
+                .collect(),
+        )
+    }
+
+    /// The zero polynomial has no degree
+    pub fn degree(&self) -> Option<usize> {
+        self.coefficients.len().checked_sub(1)
+    }
+
+    pub fn evaluate(&self, x: &BigRational) -> BigRational {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(BigRational::zero(), |value, coefficient| {
+                value * x + coefficient
+            })
+    }
+
+    pub fn minus_constant(&self, constant: &BigRational) -> Polynomial {
+        let mut coefficients = self.coefficients.clone();
+
+        match coefficients.first_mut() {
+            Some(constant_term) => *constant_term -= constant,
+            None => coefficients.push(-constant),
+        }
+
+        Polynomial::new(coefficients)
+    }
+
+    pub fn derivative(&self) -> Polynomial {
+        Polynomial::new(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(power, coefficient)| coefficient * rational(power as i128))
+                .collect(),
+        )
+    }
+
+    fn negated(&self) -> Polynomial {
+        Polynomial::new(self.coefficients.iter().map(|c| -c).collect())
+    }
+
+    // remainder of the euclidean division by a non-zero polynomial
+    fn remainder(&self, divisor: &Polynomial) -> Polynomial {
+        let divisor_degree = divisor
+            .degree()
+            .expect("Cannot divide by the zero polynomial");
+        let divisor_leading = &divisor.coefficients[divisor_degree];
+        let mut remainder = self.coefficients.clone();
+
+        while remainder.len() > divisor_degree {
+            let leading = remainder.pop().expect("Remainder should not be empty");
+            let factor = leading / divisor_leading;
+            let shift = remainder.len() - divisor_degree;
+
+            // the leading term cancels out, the other ones are reduced
+            for (power, coefficient) in divisor.coefficients[..divisor_degree].iter().enumerate() {
+                remainder[shift + power] -= &factor * coefficient;
+            }
+        }
+
+        Polynomial::new(remainder)
+    }
+
+    // p, p', then the negated remainders of the previous two
+    fn sturm_sequence(&self) -> Vec<Polynomial> {
+        let mut sequence = vec![self.clone(), self.derivative()];
+
+        loop {
+            let [.., previous, last] = sequence.as_slice() else {
+                unreachable!("Sequence should have at least two polynomials");
+            };
+
+            if last.degree().is_none() {
+                sequence.pop();
+                return sequence;
+            }
+
+            let next = previous.remainder(last).negated();
+            sequence.push(next);
+        }
+    }
+
+    // every real root has an absolute value below this bound
+    fn root_bound(&self) -> BigRational {
+        let (leading, others) = self
+            .coefficients
+            .split_last()
+            .expect("The zero polynomial has no root bound");
+
+        others
+            .iter()
+            .map(|coefficient| (coefficient / leading).abs())
+            .fold(BigRational::zero(), |bound, ratio| bound.max(ratio))
+            + BigRational::one()
+    }
+
+    fn is_root(&self, x: &BigRational) -> bool {
+        self.evaluate(x).is_zero()
+    }
+
+    /// Real roots in increasing order, each interval being at most `width` wide
+    ///
+    /// The zero polynomial vanishes everywhere and has no isolated roots.
+    pub fn isolate_roots(&self, width: &BigRational) -> Vec<Root> {
+        if self.degree().unwrap_or(0) == 0 {
+            return Vec::new();
+        }
+
+        let sturm = Sturm {
+            polynomial: self,
+            sequence: self.sturm_sequence(),
+        };
+        let bound = self.root_bound();
+        let mut roots = Vec::new();
+
+        sturm.isolate(-bound.clone(), bound, &mut roots);
+
+        roots
+            .into_iter()
+            .map(|root| sturm.refine(root, width))
+            .collect()
+    }
+
+    /// Integers of the range where the polynomial is strictly positive, as sorted disjoint ranges
+    pub fn positive_integers(&self, range: RangeInclusive<BigInt>) -> Vec<RangeInclusive<BigInt>> {
+        if range.is_empty() {
+            return Vec::new();
+        }
+
+        let (first, last) = range.into_inner();
+        let mut critical = BTreeSet::from([first.clone(), last.clone()]);
+
+        // integers around a root are checked one by one
+        for root in self.isolate_roots(&BigRational::new(1.into(), 2.into())) {
+            let (low, high) = match root {
+                Root::Exact(value) => (value.clone(), value),
+                Root::Between(low, high) => (low, high),
+            };
+            let mut integer = low.floor().to_integer().max(first.clone());
+            let end = high.ceil().to_integer().min(last.clone());
+
+            while integer <= end {
+                critical.insert(integer.clone());
+                integer += 1;
+            }
+        }
+
+        let is_positive = |integer: &BigInt| {
+            self.evaluate(&BigRational::from_integer(integer.clone())) > BigRational::zero()
+        };
+        let mut solutions: Vec<RangeInclusive<BigInt>> = Vec::new();
+        let mut previous: Option<BigInt> = None;
+
+        for integer in critical {
+            let mut push = |range: RangeInclusive<BigInt>| match solutions.last_mut() {
+                Some(last) if last.end() + 1 == *range.start() => {
+                    *last = last.start().clone()..=range.end().clone();
+                }
+                _ => solutions.push(range),
+            };
+
+            // no root lies between two consecutive critical integers, the sign stays the same
+            let gap_start = previous.map(|previous| previous + 1);
+
+            if let Some(gap_start) = gap_start.filter(|gap_start| *gap_start < integer) {
+                if is_positive(&gap_start) {
+                    push(gap_start..=&integer - 1);
+                }
+            }
+
+            if is_positive(&integer) {
+                push(integer.clone()..=integer.clone());
+            }
+
+            previous = Some(integer);
+        }
+
+        solutions
+    }
+}
+
+// counts distinct roots of a polynomial over intervals
+struct Sturm<'p> {
+    polynomial: &'p Polynomial,
+    sequence: Vec<Polynomial>,
+}
+
+impl Sturm<'_> {
+    fn sign_changes(&self, x: &BigRational) -> usize {
+        let signs: Vec<bool> = self
+            .sequence
+            .iter()
+            .map(|polynomial| polynomial.evaluate(x))
+            .filter(|value| !value.is_zero())
+            .map(|value| value.is_positive())
+            .collect();
+
+        signs.windows(2).filter(|pair| pair[0] != pair[1]).count()
+    }
+
+    // distinct roots in (low, high], neither bound being a root
+    fn count(&self, low: &BigRational, high: &BigRational) -> usize {
+        self.sign_changes(low) - self.sign_changes(high)
+    }
+
+    fn isolate(&self, low: BigRational, high: BigRational, roots: &mut Vec<Root>) {
+        match self.count(&low, &high) {
+            0 => {}
+            1 => roots.push(Root::Between(low, high)),
+            _ => {
+                let middle = (&low + &high) / rational(2);
+
+                if !self.polynomial.is_root(&middle) {
+                    self.isolate(low, middle.clone(), roots);
+                    self.isolate(middle, high, roots);
+                    return;
+                }
+
+                // shrinking a margin around the root until no other root is that close
+                let mut margin = (&high - &low) / rational(4);
+
+                while self.polynomial.is_root(&(&middle - &margin))
+                    || self.polynomial.is_root(&(&middle + &margin))
+                    || self.count(&(&middle - &margin), &(&middle + &margin)) > 1
+                {
+                    margin /= rational(2);
+                }
+
+                self.isolate(low, &middle - &margin, roots);
+                roots.push(Root::Exact(middle.clone()));
+                self.isolate(&middle + &margin, high, roots);
+            }
+        }
+    }
+
+    fn refine(&self, root: Root, width: &BigRational) -> Root {
+        let (mut low, mut high) = match root {
+            Root::Exact(_) => return root,
+            Root::Between(low, high) => (low, high),
+        };
+
+        while &(&high - &low) > width {
+            let middle = (&low + &high) / rational(2);
+
+            if self.polynomial.is_root(&middle) {
+                return Root::Exact(middle);
+            }
+
+            if self.count(&low, &middle) == 1 {
+                high = middle;
+            } else {
+                low = middle;
+            }
+        }
+
+        Root::Between(low, high)
+    }
+}
+
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.coefficients.is_empty() {
+            return write!(f, "0");
+        }
+
+        let terms = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, coefficient)| !coefficient.is_zero());
+
+        for (index, (power, coefficient)) in terms.enumerate() {
+            match (index, coefficient.is_negative()) {
+                (0, true) => write!(f, "-")?,
+                (0, false) => {}
+                (_, true) => write!(f, " - ")?,
+                (_, false) => write!(f, " + ")?,
+            }
+
+            let magnitude = coefficient.abs();
+
+            match (power, magnitude.is_integer()) {
+                (0, _) => write!(f, "{}", magnitude)?,
+                (_, true) if magnitude.is_one() => {}
+                (_, true) => write!(f, "{}", magnitude)?,
+                // keeping fractions apart from the variable
+                (_, false) => write!(f, "({})", magnitude)?,
+            }
+
+            match power {
+                0 => {}
+                1 => write!(f, "x")?,
+                _ => write!(f, "x^{}", power)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Root {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Root::Exact(value) => write!(f, "x = {}", value),
+            Root::Between(low, high) => write!(f, "x in ({}, {}]", low, high),
+        }
+    }
+}
@@ -1,84 +1,31 @@
 use std::collections::{HashMap, HashSet};
 
-#[derive(Debug, Hash)]
-enum Card {
-    Joker,
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-    Seven,
-    Eight,
-    Nine,
-    Ten,
-    Queen,
-    King,
-    Ace,
-}
-
-impl Card {
-    fn power(&self) -> i8 {
-        match &self {
-            Card::Joker => 1,
-            Card::Two => 2,
-            Card::Three => 3,
-            Card::Four => 4,
-            Card::Five => 5,
-            Card::Six => 6,
-            Card::Seven => 7,
-            Card::Eight => 8,
-            Card::Nine => 9,
-            Card::Ten => 10,
-            Card::Queen => 12,
-            Card::King => 13,
-            Card::Ace => 14,
-        }
-    }
-}
-
-impl PartialOrd for Card {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.power().partial_cmp(&other.power())
-    }
-}
+use rules::{Rules, Wildcard};
 
-impl PartialEq for Card {
-    fn eq(&self, other: &Self) -> bool {
-        core::mem::discriminant(self) == core::mem::discriminant(other)
-    }
-}
+mod rules;
 
-impl Ord for Card {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.power().cmp(&other.power())
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Card {
+    // position in the ranking of the rules, the weakest card being 0
+    strength: u8,
+    wild: bool,
 }
 
-impl Eq for Card {}
-
-impl From<char> for Card {
-    fn from(letter: char) -> Self {
-        match letter {
-            'J' => Card::Joker,
-            '2' => Card::Two,
-            '3' => Card::Three,
-            '4' => Card::Four,
-            '5' => Card::Five,
-            '6' => Card::Six,
-            '7' => Card::Seven,
-            '8' => Card::Eight,
-            '9' => Card::Nine,
-            'T' => Card::Ten,
-            'Q' => Card::Queen,
-            'K' => Card::King,
-            'A' => Card::Ace,
-            _ => panic!("Unknown card with letter '{}'", letter),
+impl Card {
+    fn parse(letter: char, rules: &Rules) -> Card {
+        let strength = rules
+            .strength(letter)
+            .unwrap_or_else(|| panic!("Unknown card with letter '{}'", letter));
+
+        Card {
+            strength,
+            wild: rules.is_wild(letter),
         }
     }
 }
 
-#[derive(Debug)]
+// from the weakest to the strongest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 enum HandType {
     HighCard,
     Pair,
@@ -90,18 +37,6 @@ enum HandType {
 }
 
 impl HandType {
-    fn power(&self) -> u8 {
-        match self {
-            HandType::HighCard => 1,
-            HandType::Pair => 2,
-            HandType::TwoPair => 3,
-            HandType::ThreeOfAKind => 4,
-            HandType::FullHouse => 5,
-            HandType::FourOfAKind => 6,
-            HandType::FiveOfAKind => 7,
-        }
-    }
-
     fn upgrade(&self, joker_count: u8) -> HandType {
         match self {
             HandType::HighCard => match joker_count {
@@ -148,30 +83,11 @@ impl HandType {
     }
 }
 
-impl PartialOrd for HandType {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.power().partial_cmp(&other.power())
-    }
-}
-
-impl PartialEq for HandType {
-    fn eq(&self, other: &Self) -> bool {
-        core::mem::discriminant(self) == core::mem::discriminant(other)
-    }
-}
-
-impl Ord for HandType {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.power().cmp(&other.power())
-    }
-}
-
-impl Eq for HandType {}
-
-#[derive(Debug)]
+// hands are compared by type first, then card by card
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 struct Hand {
-    cards: Vec<Card>,
     hand_type: HandType,
+    cards: Vec<Card>,
 }
 
 fn count_cards(cards: &[Card]) -> HashMap<&Card, u8> {
@@ -191,7 +107,7 @@ fn count_cards(cards: &[Card]) -> HashMap<&Card, u8> {
 fn counts_by_cards<'a>(card_counts: &'a HashMap<&Card, u8>) -> HashMap<u8, HashSet<&'a Card>> {
     let mut counts_by_cards: HashMap<u8, HashSet<&Card>> = HashMap::new();
 
-    for (card, count) in card_counts.into_iter() {
+    for (card, count) in card_counts.iter() {
         counts_by_cards
             .entry(*count)
             .and_modify(|matching_cards| {
@@ -246,7 +162,10 @@ fn resolve_handtype_without_joker(counts_by_cards: &HashMap<u8, HashSet<&Card>>)
 
 fn resolve_handtype(cards: &[Card]) -> HandType {
     let mut card_counts = count_cards(cards);
-    let joker_count = card_counts.remove(&Card::Joker).unwrap_or(0);
+    let joker_count: u8 = card_counts
+        .extract_if(|card, _| card.wild)
+        .map(|(_, count)| count)
+        .sum();
     let counts_by_cards = counts_by_cards(&card_counts);
 
     let basic_handtype = resolve_handtype_without_joker(&counts_by_cards);
@@ -262,39 +181,12 @@ impl Hand {
     }
 }
 
-impl PartialOrd for Hand {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match self.hand_type.partial_cmp(&other.hand_type) {
-            Some(core::cmp::Ordering::Equal) => {}
-            ord => return ord,
-        }
-
-        self.cards.partial_cmp(&other.cards)
-    }
-}
-
-impl PartialEq for Hand {
-    fn eq(&self, other: &Self) -> bool {
-        self.hand_type == other.hand_type && self.cards == other.cards
-    }
-}
-
-impl Ord for Hand {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match self.hand_type.cmp(&other.hand_type) {
-            std::cmp::Ordering::Equal => {}
-            ord => return ord,
-        }
-
-        self.cards.cmp(&other.cards)
-    }
-}
-
-impl Eq for Hand {}
-
-impl From<&str> for Hand {
-    fn from(hand: &str) -> Self {
-        let cards = hand.chars().map(|letter| Card::from(letter)).collect();
+impl Hand {
+    fn parse(hand: &str, rules: &Rules) -> Hand {
+        let cards = hand
+            .chars()
+            .map(|letter| Card::parse(letter, rules))
+            .collect();
 
         Hand::new(cards)
     }
@@ -306,24 +198,23 @@ struct PlayedHand {
     bid: u64,
 }
 
-impl From<&str> for PlayedHand {
-    fn from(line: &str) -> Self {
-        let parts = line.split(" ").collect::<Vec<&str>>();
-
-        let hand = parts.get(0).expect("Line should have a played hand");
-        let hand = Hand::from(*hand);
+impl PlayedHand {
+    fn parse(line: &str, rules: &Rules) -> PlayedHand {
+        let (hand, bid) = line
+            .split_once(' ')
+            .expect("Line should have a hand and a bid");
 
-        let bid = parts.get(1).expect("Line should have a bid");
+        let hand = Hand::parse(hand, rules);
         let bid = bid.parse::<u64>().expect("Bid should be a number");
 
         PlayedHand { hand, bid }
     }
 }
 
-fn ex2(input: &str) -> u64 {
+fn total_winnings(input: &str, rules: &Rules) -> u64 {
     let mut played_hands = input
         .lines()
-        .map(|line| PlayedHand::from(line))
+        .map(|line| PlayedHand::parse(line, rules))
         .collect::<Vec<PlayedHand>>();
 
     played_hands.sort_by(|a, b| a.hand.cmp(&b.hand));
@@ -335,8 +226,52 @@ fn ex2(input: &str) -> u64 {
         .sum()
 }
 
+fn ex1(input: &str) -> u64 {
+    total_winnings(input, &Rules::standard())
+}
+
+fn ex2(input: &str) -> u64 {
+    total_winnings(input, &Rules::jokers())
+}
+
 fn main() {
     let input = include_str!("../etc/input");
 
-    println!("{}", ex2(input));
+    let mut custom_rules: Option<Rules> = None;
+    let mut args = std::env::args().skip(1);
+
+    // any option plays a single game with part 1 rules tweaked by it
+    while let Some(arg) = args.next() {
+        let rules = custom_rules.get_or_insert_with(Rules::standard);
+
+        match arg.as_str() {
+            "--ranking" => {
+                rules.ranking = args
+                    .next()
+                    .expect("--ranking should have cards from the weakest")
+                    .chars()
+                    .collect()
+            }
+            "--joker" => {
+                let joker = args.next().expect("--joker should have a card");
+                let mut letters = joker.chars();
+
+                rules.joker = match (letters.next(), letters.next()) {
+                    (Some(letter), None) => letter,
+                    _ => panic!("Joker should be a single card, not '{}'", joker),
+                };
+                rules.wildcard = Wildcard::Strongest;
+            }
+            "--plain-joker" => rules.wildcard = Wildcard::Plain,
+            _ => panic!("Unknown argument '{}'", arg),
+        }
+    }
+
+    match custom_rules {
+        Some(rules) => println!("{}", total_winnings(input, &rules)),
+        None => {
+            println!("{}", ex1(input));
+            println!("{}", ex2(input));
+        }
+    }
 }
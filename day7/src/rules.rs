@@ -0,0 +1,49 @@
+/// What jokers do when resolving the type of a hand
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Wildcard {
+    // jokers are plain cards
+    Plain,
+    // jokers become whichever card makes the strongest hand
+    Strongest,
+}
+
+/// How cards are read and ranked in a game of Camel Cards
+#[derive(Debug, Clone)]
+pub struct Rules {
+    // card letters from the weakest to the strongest
+    pub ranking: Vec<char>,
+    pub joker: char,
+    pub wildcard: Wildcard,
+}
+
+impl Rules {
+    /// Part 1 rules: 'J' is a jack, ranked between ten and queen
+    pub fn standard() -> Rules {
+        Rules {
+            ranking: "23456789TJQKA".chars().collect(),
+            joker: 'J',
+            wildcard: Wildcard::Plain,
+        }
+    }
+
+    /// Part 2 rules: 'J' is a joker, the weakest card but standing for any other one
+    pub fn jokers() -> Rules {
+        Rules {
+            ranking: "J23456789TQKA".chars().collect(),
+            joker: 'J',
+            wildcard: Wildcard::Strongest,
+        }
+    }
+
+    /// Position of a letter in the ranking, the weakest card being 0
+    pub fn strength(&self, letter: char) -> Option<u8> {
+        self.ranking
+            .iter()
+            .position(|ranked| *ranked == letter)
+            .map(|position| position as u8)
+    }
+
+    pub fn is_wild(&self, letter: char) -> bool {
+        self.wildcard == Wildcard::Strongest && letter == self.joker
+    }
+}
@@ -0,0 +1,312 @@
+use std::{fmt, ops::RangeInclusive, str::FromStr};
+
+/// Coordinates of a cell as `(col, row)`, starting at the top-left corner
+pub type Position = (usize, usize);
+
+const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its cells, stored row after row
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        if cells.len() != width * height {
+            panic!(
+                "A {}x{} grid should have {} cells, got {}",
+                width,
+                height,
+                width * height,
+                cells.len()
+            );
+        }
+
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::new(width, height, vec![value; width * height])
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (col, row): Position) -> bool {
+        col < self.width && row < self.height
+    }
+
+    fn index_of(&self, (col, row): Position) -> Option<usize> {
+        if self.contains((col, row)) {
+            Some(row * self.width + col)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.index_of(position).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.index_of(position).map(|index| &mut self.cells[index])
+    }
+
+    /// Same as `get` but accepts coordinates outside of the grid, like `(-1, 0)`
+    pub fn get_signed(&self, col: isize, row: isize) -> Option<&T> {
+        let position = (usize::try_from(col).ok()?, usize::try_from(row).ok()?);
+
+        self.get(position)
+    }
+
+    /// Replaces the value of a cell, returning the previous one
+    pub fn set(&mut self, position: Position, value: T) -> Option<T> {
+        self.get_mut(position)
+            .map(|cell| std::mem::replace(cell, value))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+
+        (0..self.cells.len()).map(move |index| (index % width, index / width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        if row < self.height {
+            Some(&self.cells[row * self.width..(row + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // a grid without columns still has rows, all empty
+        (0..self.height).map(|row| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        let width = self.width;
+        let cells = if col < width { &self.cells[..] } else { &[] };
+
+        cells.iter().skip(col).step_by(width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// Iterates over the cells of a rectangle, silently ignoring the parts outside of the grid
+    pub fn region(
+        &self,
+        cols: RangeInclusive<usize>,
+        rows: RangeInclusive<usize>,
+    ) -> impl Iterator<Item = (Position, &T)> {
+        rows.flat_map(move |row| cols.clone().map(move |col| (col, row)))
+            .filter_map(|position| self.get(position).map(|cell| (position, cell)))
+    }
+
+    fn shifted(
+        &self,
+        (col, row): Position,
+        (col_shift, row_shift): (isize, isize),
+    ) -> Option<Position> {
+        let position = (
+            col.checked_add_signed(col_shift)?,
+            row.checked_add_signed(row_shift)?,
+        );
+
+        if self.contains(position) {
+            Some(position)
+        } else {
+            None
+        }
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        position: Position,
+        shifts: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (Position, &'a T)> {
+        shifts
+            .iter()
+            .filter_map(move |shift| self.shifted(position, *shift))
+            .map(|neighbour| {
+                (
+                    neighbour,
+                    &self.cells[neighbour.1 * self.width + neighbour.0],
+                )
+            })
+    }
+
+    /// Iterates over the cells above, right, below and left of a position
+    pub fn neighbours4(&self, position: Position) -> impl Iterator<Item = (Position, &T)> {
+        self.neighbours(position, &NEIGHBOURS_4)
+    }
+
+    /// Iterates over the cells surrounding a position, diagonals included
+    pub fn neighbours8(&self, position: Position) -> impl Iterator<Item = (Position, &T)> {
+        self.neighbours(position, &NEIGHBOURS_8)
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+
+    fn rebuild(&self, width: usize, height: usize, source: impl Fn(Position) -> Position) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let cells = (0..width * height)
+            .map(|index| {
+                let (col, row) = source((index % width, index / width));
+                self.cells[row * self.width + col].clone()
+            })
+            .collect();
+
+        Grid::new(width, height, cells)
+    }
+
+    /// Swaps rows and columns
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.rebuild(self.height, self.width, |(col, row)| (row, col))
+    }
+
+    pub fn rotate_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let height = self.height;
+
+        self.rebuild(self.height, self.width, |(col, row)| {
+            (row, height - 1 - col)
+        })
+    }
+
+    pub fn rotate_counter_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let width = self.width;
+
+        self.rebuild(self.height, self.width, |(col, row)| (width - 1 - row, col))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseGridError {
+    RaggedRow {
+        row: usize,
+        expected_width: usize,
+        width: usize,
+    },
+    InvalidCell {
+        position: Position,
+        letter: char,
+    },
+}
+
+impl fmt::Display for ParseGridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseGridError::RaggedRow {
+                row,
+                expected_width,
+                width,
+            } => write!(
+                f,
+                "row {} has {} cells while previous rows have {}",
+                row, width, expected_width
+            ),
+            ParseGridError::InvalidCell { position, letter } => {
+                write!(f, "invalid cell '{}' at {:?}", letter, position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseGridError {}
+
+/// Parses a character map, one row per line
+impl<T: TryFrom<char>> FromStr for Grid<T> {
+    type Err = ParseGridError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for (row, line) in input.lines().enumerate() {
+            let line_width = line.chars().count();
+
+            match width {
+                None => width = Some(line_width),
+                Some(expected_width) if expected_width != line_width => {
+                    return Err(ParseGridError::RaggedRow {
+                        row,
+                        expected_width,
+                        width: line_width,
+                    })
+                }
+                Some(_) => {}
+            }
+
+            for (col, letter) in line.chars().enumerate() {
+                let cell = T::try_from(letter).map_err(|_| ParseGridError::InvalidCell {
+                    position: (col, row),
+                    letter,
+                })?;
+                cells.push(cell);
+            }
+
+            height += 1;
+        }
+
+        Ok(Grid::new(width.unwrap_or(0), height, cells))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
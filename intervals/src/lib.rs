@@ -0,0 +1,230 @@
+use std::{fmt, ops::RangeInclusive};
+
+/// A set of integers stored as sorted, disjoint and non-adjacent inclusive ranges
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    ranges: Vec<RangeInclusive<u64>>,
+}
+
+// whether two sorted ranges overlap or touch, and should then be merged
+fn mergeable(left: &RangeInclusive<u64>, right: &RangeInclusive<u64>) -> bool {
+    match left.end().checked_add(1) {
+        Some(after_left) => *right.start() <= after_left,
+        None => true,
+    }
+}
+
+impl IntervalSet {
+    pub fn new() -> IntervalSet {
+        IntervalSet { ranges: Vec::new() }
+    }
+
+    // sorts and merges ranges, dropping empty ones
+    fn normalize(mut ranges: Vec<RangeInclusive<u64>>) -> IntervalSet {
+        ranges.retain(|range| !range.is_empty());
+        ranges.sort_by_key(|range| *range.start());
+
+        let mut normalized: Vec<RangeInclusive<u64>> = Vec::with_capacity(ranges.len());
+
+        for range in ranges {
+            match normalized.last_mut() {
+                Some(last) if mergeable(last, &range) => {
+                    *last = *last.start()..=*last.end().max(range.end());
+                }
+                _ => normalized.push(range),
+            }
+        }
+
+        IntervalSet { ranges: normalized }
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<u64>) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.push(range);
+
+        *self = IntervalSet::normalize(ranges);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of ranges in the set
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Number of integers in the set, which can exceed `u64::MAX`
+    pub fn count(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|range| (*range.end() - *range.start()) as u128 + 1)
+            .sum()
+    }
+
+    pub fn min(&self) -> Option<u64> {
+        self.ranges.first().map(|range| *range.start())
+    }
+
+    pub fn max(&self) -> Option<u64> {
+        self.ranges.last().map(|range| *range.end())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &RangeInclusive<u64>> {
+        self.ranges.iter()
+    }
+
+    // the range containing a value, if any
+    fn range_of(&self, value: u64) -> Option<&RangeInclusive<u64>> {
+        let following = self.ranges.partition_point(|range| *range.start() <= value);
+
+        following
+            .checked_sub(1)
+            .map(|index| &self.ranges[index])
+            .filter(|range| range.contains(&value))
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        self.range_of(value).is_some()
+    }
+
+    pub fn contains_range(&self, range: &RangeInclusive<u64>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+
+        self.range_of(*range.start())
+            .map(|container| container.end() >= range.end())
+            .unwrap_or(false)
+    }
+
+    pub fn is_superset(&self, other: &IntervalSet) -> bool {
+        other.iter().all(|range| self.contains_range(range))
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        IntervalSet::normalize(self.iter().chain(other.iter()).cloned().collect())
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = Vec::new();
+        let (mut left, mut right) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+
+        while let (Some(left_range), Some(right_range)) = (left.peek(), right.peek()) {
+            let start = *left_range.start().max(right_range.start());
+            let end = *left_range.end().min(right_range.end());
+
+            if start <= end {
+                ranges.push(start..=end);
+            }
+
+            // the range finishing first cannot overlap anything else
+            if left_range.end() < right_range.end() {
+                left.next();
+            } else {
+                right.next();
+            }
+        }
+
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut ranges = Vec::new();
+        let mut removed = other.ranges.iter().peekable();
+
+        for range in self.ranges.iter() {
+            let mut start = *range.start();
+            let end = *range.end();
+            let mut exhausted = false;
+
+            // skipping removed ranges before this one
+            while removed
+                .next_if(|removed_range| removed_range.end() < &start)
+                .is_some()
+            {}
+
+            while let Some(removed_range) = removed.peek() {
+                if *removed_range.start() > end {
+                    break;
+                }
+
+                if *removed_range.start() > start {
+                    ranges.push(start..=*removed_range.start() - 1);
+                }
+
+                match removed_range.end().checked_add(1) {
+                    Some(after_removed) if after_removed <= end => {
+                        start = after_removed;
+                        removed.next();
+                    }
+                    // the removed range goes past this one and may cut the next one too
+                    _ => {
+                        exhausted = true;
+                        break;
+                    }
+                }
+            }
+
+            if !exhausted {
+                ranges.push(start..=end);
+            }
+        }
+
+        IntervalSet { ranges }
+    }
+}
+
+impl From<RangeInclusive<u64>> for IntervalSet {
+    fn from(range: RangeInclusive<u64>) -> Self {
+        IntervalSet::normalize(vec![range])
+    }
+}
+
+impl FromIterator<RangeInclusive<u64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<u64>>>(ranges: I) -> Self {
+        IntervalSet::normalize(ranges.into_iter().collect())
+    }
+}
+
+impl Extend<RangeInclusive<u64>> for IntervalSet {
+    fn extend<I: IntoIterator<Item = RangeInclusive<u64>>>(&mut self, ranges: I) {
+        let mut all_ranges = std::mem::take(&mut self.ranges);
+        all_ranges.extend(ranges);
+
+        *self = IntervalSet::normalize(all_ranges);
+    }
+}
+
+impl IntoIterator for IntervalSet {
+    type Item = RangeInclusive<u64>;
+    type IntoIter = std::vec::IntoIter<RangeInclusive<u64>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ranges.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a IntervalSet {
+    type Item = &'a RangeInclusive<u64>;
+    type IntoIter = std::slice::Iter<'a, RangeInclusive<u64>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.ranges.iter()
+    }
+}
+
+impl fmt::Display for IntervalSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranges: Vec<String> = self
+            .ranges
+            .iter()
+            .map(|range| format!("{}..={}", range.start(), range.end()))
+            .collect();
+
+        write!(f, "{{{}}}", ranges.join(", "))
+    }
+}